@@ -0,0 +1,93 @@
+#!/usr/bin/env node
+/**
+ * Bundled Node.js Runtime Fetcher
+ *
+ * Downloads the Node.js release the app installs on first launch (NODE_VERSION
+ * in src-tauri/src/runtime/mod.rs) into src-tauri/resources/node/, together
+ * with SHASUMS256.txt and its signature. Builds with the `bundled-node`
+ * feature ship that folder as a Tauri resource, so the runtime installs
+ * without network access.
+ *
+ * Run: pnpm bundle:node [target-triple]
+ * Then: pnpm tauri build --features bundled-node --config src-tauri/tauri.bundled-node.conf.json
+ *
+ * Set NODE_MIRROR to download from a mirror of https://nodejs.org/dist.
+ */
+
+import { mkdir, readFile, rm, writeFile } from 'node:fs/promises';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const __dirname = dirname(fileURLToPath(import.meta.url));
+const ROOT = join(__dirname, '..');
+const RUNTIME_SOURCE = join(ROOT, 'src-tauri', 'src', 'runtime', 'mod.rs');
+const OUT_DIR = join(ROOT, 'src-tauri', 'resources', 'node');
+const MIRROR = (process.env.NODE_MIRROR || 'https://nodejs.org/dist').replace(/\/+$/, '');
+
+// Tauri target triple -> Node.js platform name and archive extension
+const PLATFORMS = {
+  'aarch64-apple-darwin': { platform: 'darwin-arm64', ext: 'tar.xz' },
+  'x86_64-apple-darwin': { platform: 'darwin-x64', ext: 'tar.xz' },
+  'x86_64-unknown-linux-gnu': { platform: 'linux-x64', ext: 'tar.xz' },
+  'aarch64-unknown-linux-gnu': { platform: 'linux-arm64', ext: 'tar.xz' },
+  'x86_64-pc-windows-msvc': { platform: 'win-x64', ext: 'zip' },
+};
+
+function getCurrentTarget() {
+  const { platform, arch } = process;
+  if (platform === 'darwin') {
+    return arch === 'arm64' ? 'aarch64-apple-darwin' : 'x86_64-apple-darwin';
+  }
+  if (platform === 'linux') {
+    return arch === 'arm64' ? 'aarch64-unknown-linux-gnu' : 'x86_64-unknown-linux-gnu';
+  }
+  if (platform === 'win32') {
+    return 'x86_64-pc-windows-msvc';
+  }
+  return null;
+}
+
+async function getNodeVersion() {
+  const source = await readFile(RUNTIME_SOURCE, 'utf8');
+  const match = source.match(/const NODE_VERSION: &str = "([^"]+)";/);
+  if (!match) {
+    throw new Error(`NODE_VERSION not found in ${RUNTIME_SOURCE}`);
+  }
+  return match[1];
+}
+
+async function download(url, dest) {
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`${url}: HTTP ${response.status}`);
+  }
+  await writeFile(dest, Buffer.from(await response.arrayBuffer()));
+  console.log(`  ✓ ${url}`);
+}
+
+async function main() {
+  const target = process.argv[2] || getCurrentTarget();
+  const entry = PLATFORMS[target];
+  if (!entry) {
+    throw new Error(`Unsupported target: ${target}`);
+  }
+
+  const version = await getNodeVersion();
+  const archive = `node-v${version}-${entry.platform}.${entry.ext}`;
+  console.log(`Bundling Node.js ${version} for ${target}\n`);
+
+  await rm(OUT_DIR, { recursive: true, force: true });
+  await mkdir(OUT_DIR, { recursive: true });
+
+  const base = `${MIRROR}/v${version}`;
+  await download(`${base}/${archive}`, join(OUT_DIR, archive));
+  await download(`${base}/SHASUMS256.txt`, join(OUT_DIR, 'SHASUMS256.txt'));
+  await download(`${base}/SHASUMS256.txt.sig`, join(OUT_DIR, 'SHASUMS256.txt.sig'));
+
+  console.log(`\nSaved to ${OUT_DIR}`);
+}
+
+main().catch((err) => {
+  console.error('Error:', err.message);
+  process.exit(1);
+});
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use thiserror::Error;
 
+use crate::error::AppError;
+
 const MAX_ENTRIES: usize = 500;
 
 #[derive(Error, Debug)]
@@ -107,6 +109,13 @@ impl ActivityManager {
         log.save()?;
         Ok(())
     }
+
+    /// Re-read activity.json after it was replaced on disk (e.g. by a restore)
+    pub fn reload(&self) -> Result<(), ActivityError> {
+        let mut log = self.log.lock().map_err(|_| ActivityError::LockError)?;
+        *log = ActivityLog::load()?;
+        Ok(())
+    }
 }
 
 // Helper function to generate unique IDs
@@ -155,15 +164,15 @@ pub fn log_activity(
 #[tauri::command]
 pub fn get_activity_log(
     manager: tauri::State<'_, ActivityManager>,
-) -> Result<Vec<ActivityLogEntry>, String> {
-    manager.get_entries().map_err(|e| e.to_string())
+) -> Result<Vec<ActivityLogEntry>, AppError> {
+    manager.get_entries().map_err(AppError::from)
 }
 
 #[tauri::command]
 pub fn clear_activity_log(
     manager: tauri::State<'_, ActivityManager>,
-) -> Result<(), String> {
-    manager.clear().map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    manager.clear().map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -173,7 +182,7 @@ pub fn add_activity_entry(
     details: String,
     status: String,
     path: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let entry = ActivityLogEntry {
         id: generate_id(),
         timestamp: current_timestamp(),
@@ -182,5 +191,5 @@ pub fn add_activity_entry(
         status,
         path,
     };
-    manager.add_entry(entry).map_err(|e| e.to_string())
+    manager.add_entry(entry).map_err(AppError::from)
 }
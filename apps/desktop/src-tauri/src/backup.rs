@@ -0,0 +1,860 @@
+//! Backup and Restore
+//!
+//! A backup is a single zip archive holding:
+//! - config/config.json (secrets excluded, included, or encrypted into config/secrets.enc)
+//! - data/activity.json
+//! - openclaw/openclaw.json, the workspaces under ~/.openclaw (bootstrap files and
+//!   memory) and every agent's sessions dir (sessions.json and transcripts)
+//! - manifest.json with the app version, schema version and a SHA-256 per file
+//!
+//! Workspaces with a custom path outside ~/.openclaw are not included; they point
+//! at the user's own directories.
+//!
+//! Restore validates the manifest and every hash, stops the gateway, and extracts
+//! into staging dirs next to each target. Targets are then swapped in by rename,
+//! keeping the previous contents aside so a failure part-way can be rolled back.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::activity::{log_activity, ActivityManager};
+use crate::config::{get_config_app_dir, get_data_app_dir, Config};
+use crate::error::{AppError, ErrorCode};
+use crate::search::SearchManager;
+use crate::sidecar::SidecarManager;
+
+/// Bump when the archive layout changes. Restore accepts this version and older.
+const SCHEMA_VERSION: u32 = 1;
+const MANIFEST_NAME: &str = "manifest.json";
+const SECRETS_NAME: &str = "config/secrets.enc";
+
+/// Config fields (camelCase, as stored in config.json) that hold secrets.
+/// anthropicApiKey is the single key of configs not yet migrated to apiKeys;
+/// proxyUrl can carry "user:pass@".
+const SECRET_FIELDS: &[&str] = &["apiKeys", "anthropicApiKey", "licenseKey", "proxyUrl"];
+
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Iteration counts accepted from a backup; more would hang the restore
+const PBKDF2_ITERATIONS_ALLOWED: std::ops::RangeInclusive<u32> = 100_000..=10_000_000;
+
+/// Largest manifest or secrets file read from a backup
+const MAX_METADATA_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How secrets from config.json are stored in the backup
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsMode {
+    /// Left out; restore keeps the secrets currently configured
+    Excluded,
+    /// Stored in plain text in config/config.json
+    Included,
+    /// Encrypted with a passphrase into config/secrets.enc
+    Encrypted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    File,
+    Dir,
+}
+
+/// A unit that restore replaces as a whole (e.g. "openclaw/workspace")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupItem {
+    pub path: String,
+    pub kind: ItemKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub schema_version: u32,
+    pub app_version: String,
+    /// Unix millis
+    pub created_at: i64,
+    pub secrets: SecretsMode,
+    pub items: Vec<BackupItem>,
+    pub files: Vec<BackupFile>,
+}
+
+/// Encrypted secrets blob (config/secrets.enc)
+#[derive(Serialize, Deserialize)]
+struct EncryptedSecrets {
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Archive roots and the directories they map to
+fn root_dir(root: &str) -> Option<PathBuf> {
+    match root {
+        "config" => get_config_app_dir(),
+        "data" => get_data_app_dir(),
+        "openclaw" => crate::workspace::openclaw_dir(),
+        _ => None,
+    }
+}
+
+/// Map an archive path (e.g. "openclaw/workspace/AGENTS.md") to its place on disk,
+/// rejecting absolute paths, ".." and unknown roots
+fn resolve_archive_path(path: &str) -> Result<PathBuf, AppError> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(AppError::invalid_data(format!(
+            "Unsafe path in backup: {}",
+            path
+        )));
+    }
+    let mut components = relative.components();
+    let root = components
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+        .ok_or_else(|| AppError::invalid_data(format!("Unsafe path in backup: {}", path)))?;
+    let base = root_dir(root)
+        .ok_or_else(|| AppError::invalid_data(format!("Unknown location in backup: {}", path)))?;
+    Ok(base.join(components.as_path()))
+}
+
+/// Whether `item` is one `create_backup_archive` produces. Anything else
+/// could make restore swap out a whole root (e.g. all of ~/.openclaw).
+fn is_known_item(item: &BackupItem) -> bool {
+    let parts: Vec<&str> = item.path.split('/').collect();
+    match (&item.kind, parts.as_slice()) {
+        (ItemKind::File, ["config", "config.json"])
+        | (ItemKind::File, ["data", "activity.json"])
+        | (ItemKind::File, ["openclaw", "openclaw.json"])
+        | (ItemKind::Dir, ["openclaw", "workspace"]) => true,
+        (ItemKind::Dir, ["openclaw", name]) => name
+            .strip_prefix("workspace-")
+            .is_some_and(|suffix| !suffix.is_empty()),
+        (ItemKind::Dir, ["openclaw", "agents", id, "sessions"]) => !id.is_empty(),
+        _ => false,
+    }
+}
+
+/// Serializing our own structs only fails on a bug
+fn json_error(e: serde_json::Error) -> AppError {
+    AppError::internal(e.to_string())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn encrypt_secrets(secrets: &Value, passphrase: &str) -> Result<Vec<u8>, AppError> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(secrets).map_err(json_error)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_ref())
+        .map_err(|_| AppError::internal("Failed to encrypt secrets"))?;
+
+    serde_json::to_vec_pretty(&EncryptedSecrets {
+        kdf: "pbkdf2-sha256".to_string(),
+        iterations: PBKDF2_ITERATIONS,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+    .map_err(json_error)
+}
+
+fn decrypt_secrets(blob: &[u8], passphrase: &str) -> Result<Value, AppError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| AppError::invalid_data(format!("Invalid secrets file: {}", e));
+    let encrypted: EncryptedSecrets = serde_json::from_slice(blob).map_err(|e| invalid(&e))?;
+    if encrypted.kdf != "pbkdf2-sha256" {
+        return Err(AppError::invalid_data(format!(
+            "Unsupported key derivation: {}",
+            encrypted.kdf
+        )));
+    }
+    let salt = hex::decode(&encrypted.salt).map_err(|e| invalid(&e))?;
+    let nonce = hex::decode(&encrypted.nonce).map_err(|e| invalid(&e))?;
+    let ciphertext = hex::decode(&encrypted.ciphertext).map_err(|e| invalid(&e))?;
+    if nonce.len() != 12 {
+        return Err(invalid(&"bad nonce"));
+    }
+    if !PBKDF2_ITERATIONS_ALLOWED.contains(&encrypted.iterations) {
+        return Err(invalid(&format!(
+            "unsupported iteration count {}",
+            encrypted.iterations
+        )));
+    }
+
+    let key = derive_key(passphrase, &salt, encrypted.iterations);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| {
+            AppError::new(
+                ErrorCode::WrongPassphrase,
+                "Wrong passphrase, or the secrets file is corrupted",
+            )
+        })?;
+    serde_json::from_slice(&plaintext).map_err(|e| invalid(&e))
+}
+
+/// Split config.json into (config without secrets, secrets)
+fn split_secrets(mut config: Value) -> (Value, Value) {
+    let mut secrets = serde_json::Map::new();
+    if let Some(obj) = config.as_object_mut() {
+        for field in SECRET_FIELDS {
+            if let Some(value) = obj.remove(*field) {
+                secrets.insert(field.to_string(), value);
+            }
+        }
+    }
+    (config, Value::Object(secrets))
+}
+
+fn merge_secrets(config: &mut Value, secrets: &Value) {
+    if let (Some(obj), Some(secrets)) = (config.as_object_mut(), secrets.as_object()) {
+        for (field, value) in secrets {
+            obj.insert(field.clone(), value.clone());
+        }
+    }
+}
+
+/// Writes files into the zip while recording their hashes for the manifest
+struct ArchiveWriter {
+    zip: zip::ZipWriter<fs::File>,
+    files: Vec<BackupFile>,
+}
+
+impl ArchiveWriter {
+    fn options() -> zip::write::SimpleFileOptions {
+        zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(true)
+    }
+
+    fn add_bytes(&mut self, path: &str, data: &[u8]) -> Result<(), AppError> {
+        self.zip
+            .start_file(path, Self::options())
+            .map_err(|e| AppError::zip(format!("Failed to add {}", path), e))?;
+        self.zip
+            .write_all(data)
+            .map_err(|e| AppError::io(format!("Failed to add {}", path), e))?;
+        self.files.push(BackupFile {
+            path: path.to_string(),
+            size: data.len() as u64,
+            sha256: hex::encode(Sha256::digest(data)),
+        });
+        Ok(())
+    }
+
+    fn add_file(&mut self, path: &str, source: &Path) -> Result<(), AppError> {
+        let mut input = fs::File::open(source)
+            .map_err(|e| AppError::io(format!("Failed to read {:?}", source), e))?;
+        self.zip
+            .start_file(path, Self::options())
+            .map_err(|e| AppError::zip(format!("Failed to add {}", path), e))?;
+
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = input
+                .read(&mut buf)
+                .map_err(|e| AppError::io(format!("Failed to read {:?}", source), e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+            self.zip
+                .write_all(&buf[..n])
+                .map_err(|e| AppError::io(format!("Failed to add {}", path), e))?;
+        }
+
+        self.files.push(BackupFile {
+            path: path.to_string(),
+            size,
+            sha256: hex::encode(hasher.finalize()),
+        });
+        Ok(())
+    }
+
+    /// Add a directory tree. Symlinks are skipped rather than followed.
+    fn add_dir(&mut self, prefix: &str, dir: &Path) -> Result<(), AppError> {
+        let entries =
+            fs::read_dir(dir).map_err(|e| AppError::io(format!("Failed to read {:?}", dir), e))?;
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let archive_path = format!("{}/{}", prefix, name);
+            if file_type.is_dir() {
+                self.add_dir(&archive_path, &entry.path())?;
+            } else if file_type.is_file() {
+                self.add_file(&archive_path, &entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn create_backup_archive(
+    dest: &Path,
+    secrets_mode: SecretsMode,
+    passphrase: Option<&str>,
+) -> Result<BackupManifest, AppError> {
+    let file = fs::File::create(dest)
+        .map_err(|e| AppError::io(format!("Failed to create {:?}", dest), e))?;
+    let mut writer = ArchiveWriter {
+        zip: zip::ZipWriter::new(file),
+        files: Vec::new(),
+    };
+    let mut items = Vec::new();
+
+    // config.json, with secrets handled per the selected mode
+    let config = Config::load()?;
+    let config_value = serde_json::to_value(&config).map_err(json_error)?;
+    let config_value = match secrets_mode {
+        SecretsMode::Included => config_value,
+        SecretsMode::Excluded => split_secrets(config_value).0,
+        SecretsMode::Encrypted => {
+            let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or_else(|| {
+                AppError::new(
+                    ErrorCode::PassphraseRequired,
+                    "A passphrase is required to encrypt secrets",
+                )
+            })?;
+            let (config_value, secrets) = split_secrets(config_value);
+            writer.add_bytes(SECRETS_NAME, &encrypt_secrets(&secrets, passphrase)?)?;
+            config_value
+        }
+    };
+    let config_json = serde_json::to_vec_pretty(&config_value).map_err(json_error)?;
+    writer.add_bytes("config/config.json", &config_json)?;
+    items.push(BackupItem {
+        path: "config/config.json".to_string(),
+        kind: ItemKind::File,
+    });
+
+    if let Some(activity) = get_data_app_dir()
+        .map(|d| d.join("activity.json"))
+        .filter(|p| p.is_file())
+    {
+        writer.add_file("data/activity.json", &activity)?;
+        items.push(BackupItem {
+            path: "data/activity.json".to_string(),
+            kind: ItemKind::File,
+        });
+    }
+
+    if let Some(openclaw_dir) = crate::workspace::openclaw_dir().filter(|d| d.is_dir()) {
+        let openclaw_config = openclaw_dir.join("openclaw.json");
+        if openclaw_config.is_file() {
+            writer.add_file("openclaw/openclaw.json", &openclaw_config)?;
+            items.push(BackupItem {
+                path: "openclaw/openclaw.json".to_string(),
+                kind: ItemKind::File,
+            });
+        }
+
+        // Workspaces (~/.openclaw/workspace, ~/.openclaw/workspace-<name>)
+        if let Ok(entries) = fs::read_dir(&openclaw_dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                if is_dir && (name == "workspace" || name.starts_with("workspace-")) {
+                    let prefix = format!("openclaw/{}", name);
+                    writer.add_dir(&prefix, &entry.path())?;
+                    items.push(BackupItem {
+                        path: prefix,
+                        kind: ItemKind::Dir,
+                    });
+                }
+            }
+        }
+
+        // Session metadata and transcripts for every agent
+        for sessions_dir in crate::transcripts::sessions_dirs() {
+            let Ok(relative) = sessions_dir.strip_prefix(&openclaw_dir) else {
+                continue;
+            };
+            let prefix = format!("openclaw/{}", relative.to_string_lossy().replace('\\', "/"));
+            writer.add_dir(&prefix, &sessions_dir)?;
+            items.push(BackupItem {
+                path: prefix,
+                kind: ItemKind::Dir,
+            });
+        }
+    }
+
+    let manifest = BackupManifest {
+        schema_version: SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64,
+        secrets: secrets_mode,
+        items,
+        files: writer.files.clone(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(json_error)?;
+    writer
+        .zip
+        .start_file(MANIFEST_NAME, ArchiveWriter::options())
+        .and_then(|_| writer.zip.write_all(&manifest_json).map_err(Into::into))
+        .map_err(|e| AppError::zip("Failed to write manifest", e))?;
+    writer
+        .zip
+        .finish()
+        .map_err(|e| AppError::zip("Failed to finish archive", e))?;
+
+    Ok(manifest)
+}
+
+/// Read an entry of at most `max_size` bytes. Both the size the archive
+/// claims and what actually decompresses are checked, so a zip bomb fails
+/// before it fills memory.
+fn read_entry(
+    zip: &mut zip::ZipArchive<fs::File>,
+    name: &str,
+    max_size: u64,
+) -> Result<Vec<u8>, AppError> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| AppError::invalid_data(format!("Backup is missing {}", name)))?;
+    let too_large = || {
+        AppError::invalid_data(format!(
+            "{} is larger than expected; the backup may be corrupted",
+            name
+        ))
+    };
+    if entry.size() > max_size {
+        return Err(too_large());
+    }
+    let mut data = Vec::new();
+    entry
+        .by_ref()
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut data)
+        .map_err(|e| AppError::io(format!("Failed to read {}", name), e))?;
+    if data.len() as u64 > max_size {
+        return Err(too_large());
+    }
+    Ok(data)
+}
+
+fn validate_manifest(
+    manifest: &BackupManifest,
+    zip: &zip::ZipArchive<fs::File>,
+) -> Result<(), AppError> {
+    if manifest.schema_version == 0 || manifest.schema_version > SCHEMA_VERSION {
+        return Err(AppError::invalid_data(format!(
+            "Unsupported backup schema version {} (this app supports up to {}). \
+             The backup was made by simplestclaw {}.",
+            manifest.schema_version, SCHEMA_VERSION, manifest.app_version
+        )));
+    }
+    for (i, item) in manifest.items.iter().enumerate() {
+        if !is_known_item(item) {
+            return Err(AppError::invalid_data(format!(
+                "Unexpected item in backup: {}",
+                item.path
+            )));
+        }
+        if manifest.items[..i]
+            .iter()
+            .any(|other| other.path == item.path)
+        {
+            return Err(AppError::invalid_data(format!(
+                "Item listed twice in backup: {}",
+                item.path
+            )));
+        }
+        // A file item without its file would be restored as missing
+        if item.kind == ItemKind::File && !manifest.files.iter().any(|f| f.path == item.path) {
+            return Err(AppError::invalid_data(format!(
+                "Backup is missing {}",
+                item.path
+            )));
+        }
+        resolve_archive_path(&item.path)?;
+    }
+    for file in &manifest.files {
+        resolve_archive_path(&file.path)?;
+        let covered = file.path == SECRETS_NAME
+            || manifest.items.iter().any(|item| match item.kind {
+                ItemKind::File => file.path == item.path,
+                ItemKind::Dir => file.path.starts_with(&format!("{}/", item.path)),
+            });
+        if !covered {
+            return Err(AppError::invalid_data(format!(
+                "Backup file is not part of any item: {}",
+                file.path
+            )));
+        }
+    }
+    // Every archive entry must be accounted for in the manifest
+    for name in zip.file_names() {
+        if name != MANIFEST_NAME
+            && !name.ends_with('/')
+            && !manifest.files.iter().any(|f| f.path == name)
+        {
+            return Err(AppError::invalid_data(format!(
+                "Backup contains a file missing from the manifest: {}",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// One restore target and where its new/previous contents are parked
+struct StagedItem {
+    target: PathBuf,
+    staged: PathBuf,
+    rollback: PathBuf,
+    is_dir: bool,
+    swapped: bool,
+}
+
+/// Staging area next to a target, so the final swap is a same-filesystem rename
+fn staging_root(target: &Path, token: &str) -> Result<PathBuf, AppError> {
+    let parent = target
+        .parent()
+        .ok_or_else(|| AppError::invalid_data(format!("Invalid restore target: {:?}", target)))?;
+    Ok(parent.join(format!(".simplestclaw-restore-{}", token)))
+}
+
+/// Open a backup and validate its manifest without touching anything on disk
+fn open_backup(archive: &Path) -> Result<(zip::ZipArchive<fs::File>, BackupManifest), AppError> {
+    let file = fs::File::open(archive).map_err(|e| AppError::io("Failed to open backup", e))?;
+    let mut zip =
+        zip::ZipArchive::new(file).map_err(|e| AppError::zip("Not a valid backup archive", e))?;
+    let manifest: BackupManifest =
+        serde_json::from_slice(&read_entry(&mut zip, MANIFEST_NAME, MAX_METADATA_BYTES)?)
+            .map_err(|e| AppError::invalid_data(format!("Invalid backup manifest: {}", e)))?;
+    validate_manifest(&manifest, &zip)?;
+    Ok((zip, manifest))
+}
+
+/// Work out the secrets to merge into the restored config.json
+/// (None when the backup's config already contains them)
+fn restored_secrets(
+    zip: &mut zip::ZipArchive<fs::File>,
+    manifest: &BackupManifest,
+    passphrase: Option<&str>,
+) -> Result<Option<Value>, AppError> {
+    match manifest.secrets {
+        SecretsMode::Included => Ok(None),
+        SecretsMode::Excluded => {
+            // Keep whatever is configured right now
+            let current = serde_json::to_value(Config::load()?).map_err(json_error)?;
+            Ok(Some(split_secrets(current).1))
+        }
+        SecretsMode::Encrypted => {
+            let passphrase = passphrase.filter(|p| !p.is_empty()).ok_or_else(|| {
+                AppError::new(
+                    ErrorCode::PassphraseRequired,
+                    "This backup has encrypted secrets. Please enter its passphrase.",
+                )
+            })?;
+            decrypt_secrets(
+                &read_entry(zip, SECRETS_NAME, MAX_METADATA_BYTES)?,
+                passphrase,
+            )
+            .map(Some)
+        }
+    }
+}
+
+fn restore_backup_archive(
+    zip: &mut zip::ZipArchive<fs::File>,
+    manifest: &BackupManifest,
+    secrets: Option<&Value>,
+) -> Result<(), AppError> {
+    let token = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        .to_string();
+
+    let mut staged_items = Vec::new();
+    for item in &manifest.items {
+        let target = resolve_archive_path(&item.path)?;
+        let root = staging_root(&target, &token)?;
+        let name = target
+            .file_name()
+            .ok_or_else(|| AppError::invalid_data("Invalid restore target"))?
+            .to_owned();
+        staged_items.push(StagedItem {
+            staged: root.join("staged").join(&name),
+            rollback: root.join("rollback").join(&name),
+            is_dir: item.kind == ItemKind::Dir,
+            target,
+            swapped: false,
+        });
+    }
+
+    let result = stage_and_swap(zip, manifest, secrets, &mut staged_items);
+    if let Err(ref e) = result {
+        eprintln!("[backup] Restore failed, rolling back: {}", e);
+        rollback(&staged_items);
+    }
+
+    // Clean up staging areas (and the previous contents, once swapped in for good)
+    for item in &staged_items {
+        if let Ok(root) = staging_root(&item.target, &token) {
+            let _ = fs::remove_dir_all(root);
+        }
+    }
+
+    result
+}
+
+fn stage_and_swap(
+    zip: &mut zip::ZipArchive<fs::File>,
+    manifest: &BackupManifest,
+    secrets: Option<&Value>,
+    items: &mut [StagedItem],
+) -> Result<(), AppError> {
+    // 1. Extract everything into staging, verifying hashes
+    for item in items.iter() {
+        if let Some(parent) = item.staged.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create staging dir", e))?;
+        }
+        if item.is_dir {
+            // Created up front so an empty directory is restored as empty
+            fs::create_dir_all(&item.staged)
+                .map_err(|e| AppError::io("Failed to create staging dir", e))?;
+        }
+    }
+
+    for file in &manifest.files {
+        if file.path == SECRETS_NAME {
+            continue;
+        }
+        let data = read_entry(zip, &file.path, file.size)?;
+        if data.len() as u64 != file.size || hex::encode(Sha256::digest(&data)) != file.sha256 {
+            return Err(AppError::invalid_data(format!(
+                "Checksum mismatch for {}; the backup may be corrupted",
+                file.path
+            )));
+        }
+
+        let target = resolve_archive_path(&file.path)?;
+        let item = items
+            .iter()
+            .find(|i| target == i.target || target.starts_with(&i.target))
+            .ok_or_else(|| {
+                AppError::invalid_data(format!(
+                    "Backup file is not part of any item: {}",
+                    file.path
+                ))
+            })?;
+        let staged_path = match target.strip_prefix(&item.target) {
+            Ok(rest) if !rest.as_os_str().is_empty() => item.staged.join(rest),
+            _ => item.staged.clone(),
+        };
+        if let Some(parent) = staged_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io("Failed to create staging dir", e))?;
+        }
+
+        let data = match (file.path.as_str(), secrets) {
+            ("config/config.json", Some(secrets)) => {
+                let mut config: Value = serde_json::from_slice(&data).map_err(|e| {
+                    AppError::invalid_data(format!("Invalid config in backup: {}", e))
+                })?;
+                merge_secrets(&mut config, secrets);
+                serde_json::to_vec_pretty(&config).map_err(json_error)?
+            }
+            _ => data,
+        };
+        fs::write(&staged_path, data)
+            .map_err(|e| AppError::io(format!("Failed to stage {}", file.path), e))?;
+    }
+
+    // 2. Swap staged items into place, parking the current contents for rollback
+    for item in items.iter_mut() {
+        if let Some(parent) = item.target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AppError::io(format!("Failed to create {:?}", parent), e))?;
+        }
+        if item.target.exists() {
+            if let Some(parent) = item.rollback.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| AppError::io("Failed to create rollback dir", e))?;
+            }
+            fs::rename(&item.target, &item.rollback)
+                .map_err(|e| AppError::io(format!("Failed to move aside {:?}", item.target), e))?;
+        }
+        item.swapped = true;
+        fs::rename(&item.staged, &item.target)
+            .map_err(|e| AppError::io(format!("Failed to restore {:?}", item.target), e))?;
+    }
+    Ok(())
+}
+
+/// Put back the previous contents of every target that was already swapped
+fn rollback(items: &[StagedItem]) {
+    for item in items.iter().filter(|i| i.swapped) {
+        if item.target.is_dir() {
+            let _ = fs::remove_dir_all(&item.target);
+        } else {
+            let _ = fs::remove_file(&item.target);
+        }
+        if item.rollback.exists() {
+            if let Err(e) = fs::rename(&item.rollback, &item.target) {
+                eprintln!("[backup] Failed to roll back {:?}: {}", item.target, e);
+            }
+        }
+    }
+}
+
+// Tauri commands
+
+/// Create a backup archive. `secrets` is "excluded", "included" or "encrypted"
+/// (which requires `passphrase`). Writes to `dest_path` if given, otherwise to
+/// the Downloads folder. Returns the path of the archive.
+#[tauri::command]
+pub async fn create_backup(
+    app: AppHandle,
+    dest_path: Option<String>,
+    secrets: Option<String>,
+    passphrase: Option<String>,
+) -> Result<String, AppError> {
+    let secrets_mode = match secrets.as_deref().map(|s| s.to_lowercase()).as_deref() {
+        None | Some("excluded") | Some("exclude") => SecretsMode::Excluded,
+        Some("included") | Some("include") => SecretsMode::Included,
+        Some("encrypted") | Some("encrypt") => SecretsMode::Encrypted,
+        Some(other) => {
+            return Err(AppError::invalid_input(format!(
+                "Unknown secrets mode: {}. Use 'excluded', 'included', or 'encrypted'.",
+                other
+            )))
+        }
+    };
+    let dest = match dest_path.filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            dirs::download_dir()
+                .or_else(dirs::home_dir)
+                .ok_or_else(|| {
+                    AppError::new(
+                        ErrorCode::ConfigUnavailable,
+                        "Failed to determine backup location",
+                    )
+                })?
+                .join(format!("simplestclaw-backup-{}.zip", timestamp))
+        }
+    };
+
+    let dest_clone = dest.clone();
+    let manifest = tokio::task::spawn_blocking(move || {
+        let result = create_backup_archive(&dest_clone, secrets_mode, passphrase.as_deref());
+        if result.is_err() {
+            let _ = fs::remove_file(&dest_clone);
+        }
+        result
+    })
+    .await??;
+
+    let dest_str = dest.to_string_lossy().to_string();
+    println!(
+        "[backup] Created backup with {} files at {}",
+        manifest.files.len(),
+        dest_str
+    );
+    if let Some(activity) = app.try_state::<ActivityManager>() {
+        log_activity(
+            &activity,
+            "file_write",
+            &format!("Created backup ({} files)", manifest.files.len()),
+            "success",
+            Some(&dest_str),
+        );
+    }
+    Ok(dest_str)
+}
+
+/// Restore a backup archive created by `create_backup`.
+/// Stops the gateway first; the frontend should restart it afterwards.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<BackupManifest, AppError> {
+    let app_clone = app.clone();
+    let archive = PathBuf::from(&path);
+    let manifest = tokio::task::spawn_blocking(move || {
+        // Validate and unlock secrets before touching the running gateway
+        let (mut zip, manifest) = open_backup(&archive)?;
+        let secrets = restored_secrets(&mut zip, &manifest, passphrase.as_deref())?;
+
+        println!("[backup] Stopping gateway before restore...");
+        app_clone.state::<SidecarManager>().stop()?;
+
+        restore_backup_archive(&mut zip, &manifest, secrets.as_ref())?;
+        Ok::<_, AppError>(manifest)
+    })
+    .await??;
+
+    // Transcripts were replaced; index them again from scratch
+    if let Some(search) = app.try_state::<SearchManager>() {
+        search.rebuild();
+    }
+    if let Some(activity) = app.try_state::<ActivityManager>() {
+        // activity.json was replaced on disk
+        if let Err(e) = activity.reload() {
+            eprintln!("[backup] Failed to reload activity log: {}", e);
+        }
+        log_activity(
+            &activity,
+            "file_write",
+            &format!(
+                "Restored backup from simplestclaw {} ({} files)",
+                manifest.app_version,
+                manifest.files.len()
+            ),
+            "success",
+            Some(&path),
+        );
+    }
+    println!("[backup] Restore complete");
+    Ok(manifest)
+}
@@ -0,0 +1,341 @@
+//! Disk Usage Breakdown
+//!
+//! Scans everything simplestclaw and OpenClaw keep on disk and groups it into
+//! categories (runtime, OpenClaw package, transcripts, memory, ...). Symlinks
+//! are never followed, so a workspace linking to a large folder elsewhere
+//! doesn't count it. Scans can be cancelled from the frontend.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::{
+    format_bytes, get_config_app_dir, get_data_app_dir, openclaw_npx_cache_dirs, Config,
+};
+use crate::error::{AppError, ErrorCode};
+use crate::runtime::RuntimeManager;
+
+/// Number of largest files reported per category
+const LARGEST_ENTRIES: usize = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiskUsageKind {
+    Runtime,
+    OpenclawPackage,
+    Transcripts,
+    Memory,
+    Workspace,
+    /// Workspaces at a custom path, i.e. the user's own folders
+    CustomWorkspaces,
+    ActivityLog,
+    Logs,
+    TempDownloads,
+    /// Config and anything else in the app's own directories
+    Other,
+}
+
+impl DiskUsageKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Runtime => "Node.js runtime",
+            Self::OpenclawPackage => "OpenClaw package",
+            Self::Transcripts => "Transcripts",
+            Self::Memory => "Memory",
+            Self::Workspace => "Workspace files",
+            Self::CustomWorkspaces => "Custom workspaces (not deleted)",
+            Self::ActivityLog => "Activity log",
+            Self::Logs => "Logs",
+            Self::TempDownloads => "Temporary downloads",
+            Self::Other => "Settings and other",
+        }
+    }
+
+    /// Whether "Delete all app data" removes it. Custom workspaces are left
+    /// alone, so they're reported but not counted in the total.
+    pub fn is_app_data(self) -> bool {
+        self != Self::CustomWorkspaces
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageEntry {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageCategory {
+    pub kind: DiskUsageKind,
+    pub label: String,
+    /// Counted in the total and removed by "Delete all app data"
+    pub app_data: bool,
+    /// Directories and files that were scanned for this category
+    pub paths: Vec<String>,
+    pub size_bytes: u64,
+    pub size_formatted: String,
+    pub file_count: u64,
+    /// Largest files, biggest first
+    pub largest: Vec<DiskUsageEntry>,
+}
+
+/// Hands out cancellation tokens for disk scans.
+/// Cancelling bumps the generation, which stops every scan started before it.
+#[derive(Default)]
+pub struct DiskUsageScanner {
+    generation: Arc<AtomicU64>,
+}
+
+impl DiskUsageScanner {
+    pub fn token(&self) -> CancelToken {
+        CancelToken {
+            generation: self.generation.clone(),
+            started_at: self.generation.load(Ordering::SeqCst),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Clone)]
+pub struct CancelToken {
+    generation: Arc<AtomicU64>,
+    started_at: u64,
+}
+
+impl CancelToken {
+    fn is_cancelled(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.started_at
+    }
+}
+
+/// Running totals for one category
+#[derive(Default)]
+struct Tally {
+    size: u64,
+    files: u64,
+    largest: Vec<(u64, PathBuf)>,
+}
+
+impl Tally {
+    fn add(&mut self, size: u64, path: &Path) {
+        self.size += size;
+        self.files += 1;
+        if self.largest.len() < LARGEST_ENTRIES
+            || size > self.largest.last().map(|l| l.0).unwrap_or(0)
+        {
+            self.largest.push((size, path.to_path_buf()));
+            self.largest.sort_by_key(|l| std::cmp::Reverse(l.0));
+            self.largest.truncate(LARGEST_ENTRIES);
+        }
+    }
+}
+
+fn cancelled() -> AppError {
+    AppError::new(ErrorCode::Cancelled, "Scan cancelled")
+}
+
+/// Add `path` (file or directory tree) to `tally`, skipping anything in `exclude`.
+/// Symlinks inside the tree are skipped rather than followed.
+fn scan(
+    path: &Path,
+    exclude: &[PathBuf],
+    tally: &mut Tally,
+    token: &CancelToken,
+) -> Result<(), AppError> {
+    if token.is_cancelled() {
+        return Err(cancelled());
+    }
+    if exclude.iter().any(|e| e == path) {
+        return Ok(());
+    }
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    if metadata.is_file() {
+        tally.add(metadata.len(), path);
+        return Ok(());
+    }
+    if !metadata.is_dir() {
+        return Ok(());
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let entry_path = entry.path();
+        if file_type.is_symlink() || exclude.contains(&entry_path) {
+            continue;
+        }
+        if file_type.is_dir() {
+            scan(&entry_path, exclude, tally, token)?;
+        } else if file_type.is_file() {
+            if token.is_cancelled() {
+                return Err(cancelled());
+            }
+            if let Ok(metadata) = entry.metadata() {
+                tally.add(metadata.len(), &entry_path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Files left behind by interrupted runtime downloads
+fn temp_downloads(runtime_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(runtime_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|ext| ext == "tmp").unwrap_or(false))
+        .collect()
+}
+
+/// The roots scanned for a category, plus paths to leave out of it
+/// (because another category counts them)
+struct CategoryRoots {
+    kind: DiskUsageKind,
+    roots: Vec<PathBuf>,
+    exclude: Vec<PathBuf>,
+}
+
+fn category(kind: DiskUsageKind, roots: Vec<PathBuf>, exclude: Vec<PathBuf>) -> CategoryRoots {
+    CategoryRoots {
+        kind,
+        roots,
+        exclude,
+    }
+}
+
+fn category_roots() -> Result<Vec<CategoryRoots>, AppError> {
+    let config = Config::load()?;
+    let runtime_dir = RuntimeManager::runtime_dir();
+    let temp_files = runtime_dir
+        .as_deref()
+        .map(temp_downloads)
+        .unwrap_or_default();
+    let search_dir = crate::search::index_path().and_then(|p| p.parent().map(PathBuf::from));
+    let activity_file = get_data_app_dir().map(|d| d.join("activity.json"));
+    let openclaw_dir = crate::workspace::openclaw_dir();
+    let (workspace_dirs, custom_workspace_dirs): (Vec<PathBuf>, Vec<PathBuf>) =
+        crate::workspace::all_workspace_dirs(&config)
+            .into_iter()
+            .partition(|d| openclaw_dir.as_ref().is_some_and(|o| d.starts_with(o)));
+
+    let memory_paths: Vec<PathBuf> = workspace_dirs
+        .iter()
+        .flat_map(|d| [d.join("memory"), d.join("MEMORY.md")])
+        .collect();
+
+    let mut transcripts = crate::transcripts::sessions_dirs();
+    transcripts.extend(search_dir.clone());
+
+    let logs: Vec<PathBuf> = openclaw_dir
+        .iter()
+        .map(|d| d.join("logs"))
+        .chain(std::iter::once(std::env::temp_dir().join("openclaw")))
+        .collect();
+
+    // Config/data dirs minus everything that has its own category
+    let mut app_dirs: Vec<PathBuf> = get_config_app_dir().into_iter().collect();
+    if let Some(data_dir) = get_data_app_dir() {
+        if !app_dirs.contains(&data_dir) {
+            app_dirs.push(data_dir);
+        }
+    }
+    let other_exclude: Vec<PathBuf> = runtime_dir
+        .iter()
+        .cloned()
+        .chain(search_dir)
+        .chain(activity_file.clone())
+        .collect();
+
+    Ok(vec![
+        category(
+            DiskUsageKind::Runtime,
+            runtime_dir.into_iter().collect(),
+            temp_files.clone(),
+        ),
+        category(
+            DiskUsageKind::OpenclawPackage,
+            openclaw_npx_cache_dirs(),
+            Vec::new(),
+        ),
+        category(DiskUsageKind::Transcripts, transcripts, Vec::new()),
+        category(DiskUsageKind::Memory, memory_paths.clone(), Vec::new()),
+        category(DiskUsageKind::Workspace, workspace_dirs, memory_paths),
+        category(
+            DiskUsageKind::CustomWorkspaces,
+            custom_workspace_dirs,
+            Vec::new(),
+        ),
+        category(
+            DiskUsageKind::ActivityLog,
+            activity_file.into_iter().collect(),
+            Vec::new(),
+        ),
+        category(DiskUsageKind::Logs, logs, Vec::new()),
+        category(DiskUsageKind::TempDownloads, temp_files, Vec::new()),
+        category(DiskUsageKind::Other, app_dirs, other_exclude),
+    ])
+}
+
+/// Scan every category. Blocking; run it on a blocking thread.
+pub fn scan_categories(token: &CancelToken) -> Result<Vec<DiskUsageCategory>, AppError> {
+    let mut categories = Vec::new();
+    for CategoryRoots {
+        kind,
+        roots,
+        exclude,
+    } in category_roots()?
+    {
+        let mut tally = Tally::default();
+        let mut paths = Vec::new();
+        for root in &roots {
+            if fs::symlink_metadata(root).is_err() {
+                continue;
+            }
+            scan(root, &exclude, &mut tally, token)?;
+            paths.push(root.to_string_lossy().to_string());
+        }
+
+        categories.push(DiskUsageCategory {
+            kind,
+            label: kind.label().to_string(),
+            app_data: kind.is_app_data(),
+            paths,
+            size_bytes: tally.size,
+            size_formatted: format_bytes(tally.size),
+            file_count: tally.files,
+            largest: tally
+                .largest
+                .into_iter()
+                .map(|(size_bytes, path)| DiskUsageEntry {
+                    path: path.to_string_lossy().to_string(),
+                    size_bytes,
+                })
+                .collect(),
+        });
+    }
+    Ok(categories)
+}
+
+// Tauri commands
+
+/// Stop any running `get_app_data_info` scan; it fails with `ErrorCode::Cancelled`
+#[tauri::command]
+pub fn cancel_app_data_scan(scanner: tauri::State<'_, DiskUsageScanner>) {
+    scanner.cancel();
+}
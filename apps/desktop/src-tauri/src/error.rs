@@ -0,0 +1,301 @@
+//! Error type returned by all Tauri commands
+//!
+//! Commands fail with an `AppError`, which reaches the frontend as
+//! `{ code, message, hint, details }`. `code` is stable, so the UI can branch
+//! on it and pick a localized message; `message` is the English description,
+//! `hint` what the user can do about it, and `details` any structured data
+//! (paths, sizes, exit codes) a localized message needs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::activity::ActivityError;
+use crate::config::ConfigError;
+use crate::runtime::{PreflightError, RuntimeError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// No more specific code applies; the message is still meant for the user
+    Other,
+    Io,
+    /// The config or data directory couldn't be determined
+    ConfigUnavailable,
+    /// A file on disk couldn't be parsed
+    InvalidData,
+    /// A command argument was rejected
+    InvalidInput,
+    NotFound,
+    AlreadyExists,
+    /// A long-running operation (e.g. a disk usage scan) was cancelled
+    Cancelled,
+    /// A bug rather than something the user can fix, e.g. a poisoned lock
+    Internal,
+
+    /// The transcript search index couldn't be opened, updated or queried
+    SearchIndexFailed,
+
+    // Backups
+    /// The backup has encrypted secrets and no passphrase was given
+    PassphraseRequired,
+    WrongPassphrase,
+
+    // Runtime installs
+    UnsupportedPlatform,
+    /// Node.js (or Bun, with the Bun gateway host) isn't installed
+    RuntimeNotInstalled,
+    /// The installed Bun is older than MIN_BUN
+    RuntimeIncompatible,
+    DownloadFailed,
+    InvalidNetworkSettings,
+    /// SHASUMS256.txt has no entry for the archive
+    ChecksumNotFound,
+    ChecksumMismatch,
+    SignatureInvalid,
+    ExtractFailed,
+    VerificationFailed,
+    InvalidVersionConstraint,
+    NoMatchingVersion,
+    WrongArchive,
+    InstallCancelled,
+    InstallBusy,
+
+    // Pre-flight checks
+    InsufficientSpace,
+    NotWritable,
+    ReadOnlyFilesystem,
+    Noexec,
+
+    // Gateway
+    PortInUse,
+    MissingLicenseKey,
+    MissingApiKey,
+    GatewayStartFailed,
+    GatewayExited,
+    GatewayTimeout,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub hint: Option<String>,
+    pub details: Option<Value>,
+}
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            hint: None,
+            details: None,
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn invalid_data(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidData, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// An io error with what was being done, e.g. "Failed to write AGENTS.md".
+    /// The code follows the error's kind, like `From<io::Error>`.
+    pub fn io(context: impl fmt::Display, e: std::io::Error) -> Self {
+        Self {
+            message: format!("{}: {}", context, e),
+            ..Self::from(e)
+        }
+    }
+
+    /// A zip error with what was being done; io errors keep their kind and
+    /// anything else means the archive is damaged
+    pub fn zip(context: impl fmt::Display, e: zip::result::ZipError) -> Self {
+        match e {
+            zip::result::ZipError::Io(e) => Self::io(context, e),
+            e => Self::invalid_data(format!("{}: {}", context, e)),
+        }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.hint {
+            Some(ref hint) => write!(f, "{} {}", self.message, hint),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let code = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::AlreadyExists => ErrorCode::AlreadyExists,
+            std::io::ErrorKind::InvalidData => ErrorCode::InvalidData,
+            _ => ErrorCode::Io,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+/// A blocking task panicked or was cancelled
+impl From<tokio::task::JoinError> for AppError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Self::internal(format!("Task failed: {}", e))
+    }
+}
+
+impl From<ConfigError> for AppError {
+    fn from(e: ConfigError) -> Self {
+        let code = match e {
+            ConfigError::NoConfigDir => ErrorCode::ConfigUnavailable,
+            ConfigError::Io(_) => ErrorCode::Io,
+            ConfigError::Json(_) => ErrorCode::InvalidData,
+        };
+        let error = Self::new(code, e.to_string());
+        match code {
+            ErrorCode::InvalidData => error.with_hint(
+                "config.json is damaged. Fix or delete it, or reset settings from Settings.",
+            ),
+            _ => error,
+        }
+    }
+}
+
+impl From<ActivityError> for AppError {
+    fn from(e: ActivityError) -> Self {
+        let code = match e {
+            ActivityError::NoDataDir => ErrorCode::ConfigUnavailable,
+            ActivityError::Io(_) => ErrorCode::Io,
+            ActivityError::Json(_) => ErrorCode::InvalidData,
+            ActivityError::LockError => ErrorCode::Internal,
+        };
+        Self::new(code, e.to_string())
+    }
+}
+
+impl From<PreflightError> for AppError {
+    fn from(e: PreflightError) -> Self {
+        let message = e.to_string();
+        match e {
+            PreflightError::InsufficientSpace {
+                path,
+                needed_mb,
+                available_mb,
+            } => Self::new(ErrorCode::InsufficientSpace, message)
+                .with_hint("Free up some space and try again.")
+                .with_details(json!({
+                    "path": path,
+                    "neededMb": needed_mb,
+                    "availableMb": available_mb,
+                })),
+            PreflightError::NotWritable { path, .. } => Self::new(ErrorCode::NotWritable, message)
+                .with_hint("Check the folder's permissions and try again.")
+                .with_details(json!({ "path": path })),
+            PreflightError::ReadOnly(path) => Self::new(ErrorCode::ReadOnlyFilesystem, message)
+                .with_hint("Remount it read-write and try again.")
+                .with_details(json!({ "path": path })),
+            PreflightError::NoExec(path) => Self::new(ErrorCode::Noexec, message)
+                .with_hint("Remount it without noexec or ask your administrator.")
+                .with_details(json!({ "path": path })),
+        }
+    }
+}
+
+impl From<RuntimeError> for AppError {
+    fn from(e: RuntimeError) -> Self {
+        Self::from(&e)
+    }
+}
+
+/// Install results are shared between callers, so they arrive behind an Arc
+impl From<&RuntimeError> for AppError {
+    fn from(e: &RuntimeError) -> Self {
+        const RETRY: &str = "Check your internet connection and try again.";
+
+        let message = e.to_string();
+        match e {
+            RuntimeError::UnsupportedPlatform => Self::new(ErrorCode::UnsupportedPlatform, message),
+            RuntimeError::NoRuntimeDir => Self::new(ErrorCode::ConfigUnavailable, message),
+            RuntimeError::Download(_) | RuntimeError::Stalled(_) => {
+                Self::new(ErrorCode::DownloadFailed, message).with_hint(RETRY)
+            }
+            RuntimeError::Http(status) => Self::new(ErrorCode::DownloadFailed, message)
+                .with_hint(RETRY)
+                .with_details(json!({ "status": status })),
+            RuntimeError::Network(_) => Self::new(ErrorCode::InvalidNetworkSettings, message)
+                .with_hint("Check the mirror, proxy and certificate settings."),
+            RuntimeError::Io(_) => Self::new(ErrorCode::Io, message),
+            RuntimeError::Preflight(e) => Self::from(e.clone()),
+            RuntimeError::ChecksumNotFound(file) => Self::new(ErrorCode::ChecksumNotFound, message)
+                .with_hint("If you use a mirror, make sure it serves official Node.js releases.")
+                .with_details(json!({ "file": file })),
+            RuntimeError::ChecksumMismatch {
+                file,
+                expected,
+                actual,
+            } => Self::new(ErrorCode::ChecksumMismatch, message)
+                .with_hint("The download may be corrupted or tampered with. Try again.")
+                .with_details(json!({
+                    "file": file,
+                    "expected": expected,
+                    "actual": actual,
+                })),
+            RuntimeError::Signature(_) => Self::new(ErrorCode::SignatureInvalid, message)
+                .with_hint("If you use a mirror, make sure it serves official Node.js releases."),
+            RuntimeError::Extract(_) | RuntimeError::UnsafeArchive(_) => {
+                Self::new(ErrorCode::ExtractFailed, message)
+            }
+            RuntimeError::Verification(_) => Self::new(ErrorCode::VerificationFailed, message)
+                .with_hint("Reinstall the runtime from Settings."),
+            RuntimeError::Cancelled => Self::new(ErrorCode::InstallCancelled, message),
+            RuntimeError::Busy => Self::new(ErrorCode::InstallBusy, message)
+                .with_hint("Wait for the current installation to finish."),
+            RuntimeError::Task(_) => Self::new(ErrorCode::Internal, message),
+            RuntimeError::VersionConstraint(_) => {
+                Self::new(ErrorCode::InvalidVersionConstraint, message)
+                    .with_hint("Use a semver range such as \">=25.6 <26\" or an exact version.")
+            }
+            RuntimeError::NoMatchingVersion(constraint) => {
+                Self::new(ErrorCode::NoMatchingVersion, message)
+                    .with_details(json!({ "constraint": constraint }))
+            }
+            RuntimeError::WrongArchive { expected, actual } => {
+                Self::new(ErrorCode::WrongArchive, message)
+                    .with_hint(format!("Download {} instead.", expected))
+                    .with_details(json!({ "expected": expected, "actual": actual }))
+            }
+        }
+    }
+}
+
+impl From<std::sync::Arc<RuntimeError>> for AppError {
+    fn from(e: std::sync::Arc<RuntimeError>) -> Self {
+        Self::from(&*e)
+    }
+}
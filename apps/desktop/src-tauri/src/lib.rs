@@ -1,11 +1,23 @@
 mod activity;
+mod backup;
 mod config;
+mod dates;
+mod disk_usage;
+mod error;
+mod memory;
 pub mod runtime;
+mod reset;
+mod retention;
+mod search;
 mod sidecar;
+mod transcripts;
+mod workspace;
 
 use activity::ActivityManager;
 use config::ApiMode;
+use disk_usage::DiskUsageScanner;
 use runtime::RuntimeManager;
+use search::SearchManager;
 use sidecar::{SidecarManager, kill_orphaned_gateway_processes};
 use tauri::{Emitter, Listener, Manager};
 
@@ -28,6 +40,8 @@ pub fn run() {
             app.manage(SidecarManager::default());
             app.manage(RuntimeManager::default());
             app.manage(ActivityManager::default());
+            app.manage(SearchManager::default());
+            app.manage(DiskUsageScanner::default());
 
             // Register deep link handler for simplestclaw:// URLs
             let handle = app.handle().clone();
@@ -40,15 +54,70 @@ pub fn run() {
             // Auto-install runtime in background if not installed
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                if !RuntimeManager::is_installed() {
-                    println!("[runtime] Node.js runtime not found, starting download...");
-                    if let Some(manager) = app_handle.try_state::<RuntimeManager>() {
-                        if let Err(e) = manager.install().await {
-                            eprintln!("[runtime] Failed to install: {}", e);
+                RuntimeManager::cleanup_interrupted_install();
+                // Status polls use the cached result, so probe off the async runtime now
+                let _ = tokio::task::spawn_blocking(runtime::detect_system_node).await;
+                let config = config::Config::load().unwrap_or_default();
+                if config.gateway_host == config::GatewayHost::Bun {
+                    if RuntimeManager::find_bun().is_some_and(|bun| bun.compatible) {
+                        println!("[runtime] Gateway runs on Bun, skipping Node.js download");
+                    } else if let Some(manager) = app_handle.try_state::<RuntimeManager>() {
+                        println!("[runtime] Gateway runs on Bun, installing it...");
+                        if let Err(e) = manager.install_bun(&app_handle).await {
+                            eprintln!("[runtime] Failed to install Bun: {}", e);
                         }
                     }
+                    return;
+                }
+                if let Some(system) = RuntimeManager::preferred_system_node(&config) {
+                    println!(
+                        "[runtime] Using system Node.js {} at {}, skipping download",
+                        system.version.as_deref().unwrap_or("(unknown version)"),
+                        system.node_path
+                    );
+                    return;
+                }
+                // An offline build installs the version it ships with
+                if !RuntimeManager::has_bundled_runtime(&app_handle) {
+                    if let Err(e) = RuntimeManager::refresh_version().await {
+                        eprintln!("[runtime] Failed to resolve Node.js version: {}", e);
+                    }
+                }
+                if !RuntimeManager::is_installed() {
+                    println!("[runtime] Node.js runtime not found, starting download...");
+                } else if !RuntimeManager::is_correct_version() {
+                    // The installed runtime keeps working until the new one is in place
+                    println!("[runtime] Newer Node.js available, upgrading in the background...");
                 } else {
                     println!("[runtime] Node.js runtime already installed");
+                    return;
+                }
+                if let Some(manager) = app_handle.try_state::<RuntimeManager>() {
+                    if let Err(e) = manager.install(&app_handle).await {
+                        eprintln!("[runtime] Failed to install: {}", e);
+                    }
+                }
+            });
+
+            // Keep the transcript search index up to date as sessions are appended to
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    if let Some(search) = app_handle.try_state::<SearchManager>() {
+                        if let Err(e) = search.refresh().await {
+                            eprintln!("[search] Failed to refresh index: {}", e);
+                        }
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(search::REFRESH_INTERVAL_SECS)).await;
+                }
+            });
+
+            // Enforce the transcript/memory retention policy
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    retention::run_scheduled(&app_handle).await;
+                    tokio::time::sleep(std::time::Duration::from_secs(retention::RETENTION_INTERVAL_SECS)).await;
                 }
             });
 
@@ -69,6 +138,7 @@ pub fn run() {
             config::set_api_key,
             config::set_provider,
             config::has_api_key,
+            config::clear_api_keys,
             config::get_api_mode,
             config::set_api_mode,
             config::set_license_key,
@@ -76,8 +146,44 @@ pub fn run() {
             config::set_selected_model,
             config::set_tool_profile,
             config::set_allow_exec,
+            config::set_verify_node_signature,
+            config::set_network_settings,
+            config::set_gateway_host,
+            config::set_parallel_download,
+            config::set_node_version_constraint,
+            config::set_runtime_preference,
             config::get_app_data_info,
+            disk_usage::cancel_app_data_scan,
             config::delete_all_app_data,
+            // Workspaces
+            workspace::list_workspaces,
+            workspace::create_workspace,
+            workspace::update_workspace,
+            workspace::delete_workspace,
+            workspace::set_active_workspace,
+            // Memory
+            memory::list_memory_files,
+            memory::read_memory_file,
+            memory::write_memory_file,
+            memory::delete_memory_file,
+            memory::prune_memory,
+            memory::export_memory,
+            // Transcripts
+            transcripts::list_sessions,
+            transcripts::get_session,
+            transcripts::export_session,
+            // Search
+            search::search_transcripts,
+            search::refresh_search_index,
+            // Backup
+            backup::create_backup,
+            backup::restore_backup,
+            // Reset
+            reset::reset_app_data,
+            // Retention
+            retention::get_retention_policy,
+            retention::set_retention_policy,
+            retention::apply_retention_policy,
             // Gateway
             sidecar::start_gateway,
             sidecar::stop_gateway,
@@ -85,6 +191,13 @@ pub fn run() {
             // Runtime
             runtime::get_runtime_status,
             runtime::install_runtime,
+            runtime::import_runtime_archive,
+            runtime::cancel_runtime_install,
+            runtime::check_runtime,
+            runtime::repair_runtime,
+            runtime::detect_system_node,
+            runtime::install_bun,
+            runtime::detect_bun,
             runtime::is_runtime_installed,
             runtime::needs_runtime_upgrade,
             // Activity
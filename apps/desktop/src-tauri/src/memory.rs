@@ -0,0 +1,246 @@
+//! Agent Memory Management
+//!
+//! OpenClaw writes daily memory logs to `<workspace>/memory/` (e.g. `2026-02-14.md`).
+//! These commands let users inspect, correct and purge what the agent
+//! "remembered", and export it as a zip archive.
+
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::activity::{log_activity, ActivityManager};
+use crate::config::Config;
+use crate::dates::parse_date_days;
+use crate::error::{AppError, ErrorCode};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A file in the active workspace's memory directory
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryFile {
+    pub name: String,
+    /// Date the entry is for ("YYYY-MM-DD"), parsed from daily log file names
+    pub date: Option<String>,
+    pub size_bytes: u64,
+    /// Last modified time (Unix millis)
+    pub modified: i64,
+}
+
+/// Memory directory of the active workspace
+fn memory_dir() -> Result<PathBuf, AppError> {
+    let config = Config::load()?;
+    let workspace_dir = crate::workspace::active_workspace_dir(&config).ok_or_else(|| {
+        AppError::new(ErrorCode::ConfigUnavailable, "Failed to get home directory")
+    })?;
+    Ok(workspace_dir.join("memory"))
+}
+
+/// Resolve a memory file name to a path inside the memory directory.
+/// Only plain file names are accepted so the frontend can't reach outside it.
+fn memory_file_path(name: &str) -> Result<PathBuf, AppError> {
+    let is_plain_name = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && Path::new(name).file_name().map(|n| n == name).unwrap_or(false);
+    if !is_plain_name {
+        return Err(AppError::invalid_input(format!(
+            "Invalid memory file name: {}",
+            name
+        )));
+    }
+    Ok(memory_dir()?.join(name))
+}
+
+/// Date prefix of a daily log name, e.g. "2026-02-14.md" or "2026-02-14-notes.md"
+fn date_from_name(name: &str) -> Option<String> {
+    let prefix = name.get(..10)?;
+    parse_date_days(prefix).map(|_| prefix.to_string())
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Files in a memory directory, newest first
+pub fn list_files(dir: &Path) -> Result<Vec<MemoryFile>, AppError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    let entries = fs::read_dir(dir).map_err(|e| AppError::io("Failed to read memory dir", e))?;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        files.push(MemoryFile {
+            date: date_from_name(&name),
+            size_bytes: metadata.len(),
+            modified: modified_millis(&metadata),
+            name,
+        });
+    }
+
+    // Newest first
+    files.sort_by(|a, b| b.date.cmp(&a.date).then(b.modified.cmp(&a.modified)));
+    Ok(files)
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn list_memory_files() -> Result<Vec<MemoryFile>, AppError> {
+    list_files(&memory_dir()?)
+}
+
+#[tauri::command]
+pub fn read_memory_file(name: String) -> Result<String, AppError> {
+    let path = memory_file_path(&name)?;
+    fs::read_to_string(&path).map_err(|e| AppError::io(format!("Failed to read {}", name), e))
+}
+
+#[tauri::command]
+pub fn write_memory_file(
+    activity: tauri::State<'_, ActivityManager>,
+    name: String,
+    content: String,
+) -> Result<(), AppError> {
+    let path = memory_file_path(&name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create memory dir", e))?;
+    }
+    fs::write(&path, content).map_err(|e| AppError::io(format!("Failed to write {}", name), e))?;
+    log_activity(
+        &activity,
+        "file_write",
+        &format!("Edited memory file {}", name),
+        "success",
+        Some(&path.to_string_lossy()),
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_memory_file(
+    activity: tauri::State<'_, ActivityManager>,
+    name: String,
+) -> Result<(), AppError> {
+    let path = memory_file_path(&name)?;
+    fs::remove_file(&path).map_err(|e| AppError::io(format!("Failed to delete {}", name), e))?;
+    log_activity(
+        &activity,
+        "file_write",
+        &format!("Deleted memory file {}", name),
+        "success",
+        Some(&path.to_string_lossy()),
+    );
+    Ok(())
+}
+
+/// Delete memory files older than `max_age_days`.
+/// Daily logs are aged by the date in their name, other files by modification time.
+/// Returns the names of the deleted files.
+#[tauri::command]
+pub fn prune_memory(
+    activity: tauri::State<'_, ActivityManager>,
+    max_age_days: u32,
+) -> Result<Vec<String>, AppError> {
+    let dir = memory_dir()?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let today = now / SECONDS_PER_DAY;
+
+    let mut removed = Vec::new();
+    for file in list_files(&dir)? {
+        let age_days = match file.date.as_deref().and_then(parse_date_days) {
+            Some(days) => today - days,
+            None => (now - file.modified / 1000) / SECONDS_PER_DAY,
+        };
+        if age_days <= max_age_days as i64 {
+            continue;
+        }
+        match fs::remove_file(dir.join(&file.name)) {
+            Ok(()) => removed.push(file.name),
+            Err(e) => eprintln!("[memory] Failed to delete {}: {}", file.name, e),
+        }
+    }
+
+    if !removed.is_empty() {
+        log_activity(
+            &activity,
+            "file_write",
+            &format!(
+                "Pruned {} memory file(s) older than {} days",
+                removed.len(),
+                max_age_days
+            ),
+            "success",
+            Some(&dir.to_string_lossy()),
+        );
+    }
+    Ok(removed)
+}
+
+/// Bundle the memory directory into a zip archive.
+/// Writes to `dest_path` if given, otherwise to the Downloads folder.
+/// Returns the path of the written archive.
+#[tauri::command]
+pub async fn export_memory(dest_path: Option<String>) -> Result<String, AppError> {
+    let dir = memory_dir()?;
+    let dest = match dest_path.filter(|p| !p.is_empty()) {
+        Some(p) => PathBuf::from(p),
+        None => {
+            let downloads = dirs::download_dir()
+                .or_else(dirs::home_dir)
+                .ok_or_else(|| {
+                    AppError::new(
+                        ErrorCode::ConfigUnavailable,
+                        "Failed to determine export location",
+                    )
+                })?;
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            downloads.join(format!("simplestclaw-memory-{}.zip", timestamp))
+        }
+    };
+
+    let path = tokio::task::spawn_blocking(move || {
+        let file = fs::File::create(&dest)
+            .map_err(|e| AppError::io(format!("Failed to create {:?}", dest), e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        for entry in list_files(&dir)? {
+            let contents = fs::read(dir.join(&entry.name))
+                .map_err(|e| AppError::io(format!("Failed to read {}", entry.name), e))?;
+            zip.start_file(format!("memory/{}", entry.name), options)
+                .map_err(|e| AppError::zip(format!("Failed to add {}", entry.name), e))?;
+            zip.write_all(&contents)
+                .map_err(|e| AppError::io(format!("Failed to add {}", entry.name), e))?;
+        }
+
+        zip.finish().map_err(|e| AppError::zip("Failed to finish archive", e))?;
+        println!("[memory] Exported memory to {:?}", dest);
+        Ok::<_, AppError>(dest.to_string_lossy().to_string())
+    })
+    .await??;
+    Ok(path)
+}
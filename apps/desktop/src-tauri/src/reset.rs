@@ -0,0 +1,353 @@
+//! Granular Data Reset
+//!
+//! `delete_all_app_data` wipes everything at once. These commands reset one
+//! category at a time (credentials, settings, runtime, package cache, sessions,
+//! memory, activity log) and can preview exactly what would be removed first.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::activity::{log_activity, ActivityManager};
+use crate::config::{
+    calculate_dir_size, format_bytes, get_config_app_dir, get_data_app_dir,
+    openclaw_npx_cache_dirs, Config,
+};
+use crate::error::AppError;
+use crate::runtime::RuntimeManager;
+use crate::search::SearchManager;
+use crate::sidecar::SidecarManager;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResetCategory {
+    /// API keys, license key, account email and the download proxy (which
+    /// can carry a password)
+    Credentials,
+    /// Everything else in config.json (model, tool profile, workspaces, ...)
+    Settings,
+    /// Bundled Node.js runtime
+    Runtime,
+    /// openclaw package in the npx cache
+    PackageCache,
+    /// Session metadata, transcripts and the search index
+    Sessions,
+    /// Agent memory in every workspace
+    Memory,
+    ActivityLog,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetAction {
+    /// The path is removed
+    Delete,
+    /// The file is kept but some of its contents are cleared
+    Rewrite,
+}
+
+/// A path touched by a reset
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetTarget {
+    pub category: ResetCategory,
+    pub path: String,
+    pub action: ResetAction,
+    pub size_bytes: u64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPlan {
+    pub dry_run: bool,
+    pub targets: Vec<ResetTarget>,
+    /// Targets that could not be removed (path and error), empty on dry runs
+    pub failed: Vec<String>,
+    pub total_size_bytes: u64,
+    pub total_size_formatted: String,
+}
+
+fn config_file() -> Option<PathBuf> {
+    get_config_app_dir().map(|d| d.join("config.json"))
+}
+
+fn openclaw_config_file() -> Option<PathBuf> {
+    crate::workspace::openclaw_dir().map(|d| d.join("openclaw.json"))
+}
+
+fn path_size(path: &PathBuf) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => calculate_dir_size(path),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Work out which existing paths a reset of `categories` would touch
+fn plan(categories: &[ResetCategory]) -> Result<Vec<ResetTarget>, AppError> {
+    let config = Config::load()?;
+    let has = |category| categories.contains(&category);
+    let mut planned: Vec<(ResetCategory, PathBuf, ResetAction, String)> = Vec::new();
+
+    if let Some(path) = config_file() {
+        match (
+            has(ResetCategory::Credentials),
+            has(ResetCategory::Settings),
+        ) {
+            (true, true) => planned.push((
+                ResetCategory::Settings,
+                path,
+                ResetAction::Delete,
+                "App settings and credentials".to_string(),
+            )),
+            (true, false) => planned.push((
+                ResetCategory::Credentials,
+                path,
+                ResetAction::Rewrite,
+                "Remove API keys, license key, account email and proxy".to_string(),
+            )),
+            (false, true) => planned.push((
+                ResetCategory::Settings,
+                path,
+                ResetAction::Rewrite,
+                "Restore default settings, keeping credentials".to_string(),
+            )),
+            (false, false) => {}
+        }
+    }
+
+    // openclaw.json is regenerated on every gateway start, and holds the license key
+    // in managed mode
+    if has(ResetCategory::Credentials) || has(ResetCategory::Settings) {
+        if let Some(path) = openclaw_config_file() {
+            let category = if has(ResetCategory::Credentials) {
+                ResetCategory::Credentials
+            } else {
+                ResetCategory::Settings
+            };
+            planned.push((
+                category,
+                path,
+                ResetAction::Delete,
+                "Generated gateway config".to_string(),
+            ));
+        }
+    }
+
+    if has(ResetCategory::Runtime) {
+        if let Some(path) = RuntimeManager::runtime_dir() {
+            planned.push((
+                ResetCategory::Runtime,
+                path,
+                ResetAction::Delete,
+                "Node.js runtime".to_string(),
+            ));
+        }
+    }
+
+    if has(ResetCategory::PackageCache) {
+        for path in openclaw_npx_cache_dirs() {
+            planned.push((
+                ResetCategory::PackageCache,
+                path,
+                ResetAction::Delete,
+                "Cached openclaw package".to_string(),
+            ));
+        }
+    }
+
+    if has(ResetCategory::Sessions) {
+        for path in crate::transcripts::sessions_dirs() {
+            planned.push((
+                ResetCategory::Sessions,
+                path,
+                ResetAction::Delete,
+                "Session transcripts".to_string(),
+            ));
+        }
+        if let Some(path) = crate::search::index_path().and_then(|p| p.parent().map(PathBuf::from))
+        {
+            planned.push((
+                ResetCategory::Sessions,
+                path,
+                ResetAction::Delete,
+                "Transcript search index".to_string(),
+            ));
+        }
+    }
+
+    if has(ResetCategory::Memory) {
+        for workspace_dir in crate::workspace::all_workspace_dirs(&config) {
+            // File by file rather than the whole directory: a custom workspace
+            // is the user's own folder, where memory/ may hold anything
+            let memory_dir = workspace_dir.join("memory");
+            for file in crate::memory::list_files(&memory_dir).unwrap_or_default() {
+                planned.push((
+                    ResetCategory::Memory,
+                    memory_dir.join(&file.name),
+                    ResetAction::Delete,
+                    "Memory log".to_string(),
+                ));
+            }
+            planned.push((
+                ResetCategory::Memory,
+                workspace_dir.join("MEMORY.md"),
+                ResetAction::Delete,
+                "Long-term memory".to_string(),
+            ));
+        }
+    }
+
+    if has(ResetCategory::ActivityLog) {
+        if let Some(path) = get_data_app_dir().map(|d| d.join("activity.json")) {
+            planned.push((
+                ResetCategory::ActivityLog,
+                path,
+                ResetAction::Delete,
+                "Activity log".to_string(),
+            ));
+        }
+    }
+
+    Ok(planned
+        .into_iter()
+        .filter(|(_, path, _, _)| fs::symlink_metadata(path).is_ok())
+        .map(|(category, path, action, description)| ResetTarget {
+            category,
+            size_bytes: path_size(&path),
+            path: path.to_string_lossy().to_string(),
+            action,
+            description,
+        })
+        .collect())
+}
+
+/// Clear credentials and/or settings in config.json, keeping the rest
+fn rewrite_config(categories: &[ResetCategory]) -> Result<(), AppError> {
+    let current = Config::load()?;
+    let config = if categories.contains(&ResetCategory::Settings) {
+        // Keys belong to their providers and the API mode, so those stay with them
+        Config {
+            provider: current.provider,
+            api_keys: current.api_keys,
+            api_mode: current.api_mode,
+            license_key: current.license_key,
+            user_email: current.user_email,
+            proxy_url: current.proxy_url,
+            ..Config::default()
+        }
+    } else {
+        Config {
+            api_keys: BTreeMap::new(),
+            license_key: None,
+            user_email: None,
+            proxy_url: None,
+            ..current
+        }
+    };
+    Ok(config.save()?)
+}
+
+fn execute(targets: &[ResetTarget], categories: &[ResetCategory]) -> Vec<String> {
+    let mut failed = Vec::new();
+    for target in targets {
+        let path = PathBuf::from(&target.path);
+        println!(
+            "[reset] {:?} {:?} ({})",
+            target.action, path, target.description
+        );
+
+        let result = match target.action {
+            ResetAction::Rewrite => rewrite_config(categories),
+            ResetAction::Delete => match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    fs::remove_dir_all(&path).map_err(AppError::from)
+                }
+                Ok(_) => fs::remove_file(&path).map_err(AppError::from),
+                Err(_) => Ok(()),
+            },
+        };
+        if let Err(e) = result {
+            eprintln!("[reset] Failed to reset {:?}: {}", path, e);
+            failed.push(format!("{}: {}", target.path, e));
+        }
+    }
+    failed
+}
+
+// Tauri commands
+
+/// Reset the selected categories of app data.
+/// With `dry_run` nothing is touched and the returned plan lists what would be
+/// removed. Otherwise the gateway is stopped first.
+#[tauri::command]
+pub async fn reset_app_data(
+    app: AppHandle,
+    categories: Vec<ResetCategory>,
+    dry_run: bool,
+) -> Result<ResetPlan, AppError> {
+    if categories.is_empty() {
+        return Err(AppError::invalid_input(
+            "Select at least one category to reset",
+        ));
+    }
+
+    let app_clone = app.clone();
+    let categories_clone = categories.clone();
+    let (targets, failed) = tokio::task::spawn_blocking(move || {
+        let targets = plan(&categories_clone)?;
+        if dry_run {
+            return Ok::<_, AppError>((targets, Vec::new()));
+        }
+
+        println!("[reset] Stopping gateway before reset...");
+        app_clone.state::<SidecarManager>().stop()?;
+        if categories_clone.contains(&ResetCategory::Sessions) {
+            if let Some(search) = app_clone.try_state::<SearchManager>() {
+                search.close();
+            }
+        }
+
+        let failed = execute(&targets, &categories_clone);
+        Ok((targets, failed))
+    })
+    .await??;
+
+    let total_size_bytes = targets.iter().map(|t| t.size_bytes).sum();
+
+    if !dry_run {
+        if let Some(activity) = app.try_state::<ActivityManager>() {
+            if categories.contains(&ResetCategory::ActivityLog) {
+                // activity.json is gone; drop the in-memory copy too
+                let _ = activity.clear();
+            }
+            let names: Vec<String> = categories.iter().map(|c| format!("{:?}", c)).collect();
+            log_activity(
+                &activity,
+                "file_write",
+                &format!(
+                    "Reset {} ({} freed)",
+                    names.join(", "),
+                    format_bytes(total_size_bytes)
+                ),
+                if failed.is_empty() {
+                    "success"
+                } else {
+                    "error"
+                },
+                None,
+            );
+        }
+    }
+
+    Ok(ResetPlan {
+        dry_run,
+        targets,
+        failed,
+        total_size_bytes,
+        total_size_formatted: format_bytes(total_size_bytes),
+    })
+}
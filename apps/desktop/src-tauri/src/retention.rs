@@ -0,0 +1,449 @@
+//! Retention Policy
+//!
+//! Transcripts and memory logs grow without limit. The retention policy in
+//! Config caps them by age, total size and number of sessions. A background
+//! job applies it periodically and records what it pruned in the activity log.
+//! Pruned files can be moved into a zip archive instead of being deleted.
+//!
+//! - Age applies to sessions (by last modification) and daily memory logs
+//!   (by the date in their name) in every workspace.
+//! - Size and keep-last-N apply to sessions, oldest first.
+//! - The newest session of each agent is never pruned; the gateway may still
+//!   be writing to it.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::activity::{log_activity, ActivityManager};
+use crate::config::{format_bytes, get_data_app_dir, Config, RetentionPolicy};
+use crate::dates::parse_date_days;
+use crate::error::{AppError, ErrorCode};
+use crate::transcripts::TranscriptFile;
+
+/// How often the background job applies the policy
+pub const RETENTION_INTERVAL_SECS: u64 = 60 * 60;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// What a retention run pruned (or would prune, on a dry run)
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    /// Ids of pruned sessions
+    pub sessions: Vec<String>,
+    /// Paths of pruned memory files
+    pub memory_files: Vec<String>,
+    pub bytes_freed: u64,
+    /// Archive the pruned files were moved into, if archiving is on
+    pub archive_path: Option<String>,
+}
+
+impl RetentionReport {
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.memory_files.is_empty()
+    }
+}
+
+/// A file selected for pruning
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+    /// Name inside the archive
+    archive_name: String,
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// Archive entry name: relative to ~/.openclaw when possible
+fn archive_name(path: &Path) -> String {
+    let relative = crate::workspace::openclaw_dir()
+        .and_then(|base| path.strip_prefix(base).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| {
+            // Custom workspace outside ~/.openclaw: keep the last few components,
+            // under a hash of the directory so same-named workspaces don't collide
+            let parts: Vec<_> = path.components().rev().take(3).collect();
+            let dir = path.parent().unwrap_or(path).to_string_lossy();
+            let dir_hash = hex::encode(Sha256::digest(dir.as_bytes()));
+            PathBuf::from("external")
+                .join(&dir_hash[..12])
+                .join(parts.into_iter().rev().collect::<PathBuf>())
+        });
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Sessions to prune, given transcripts sorted newest first
+fn select_sessions(policy: &RetentionPolicy, files: &[TranscriptFile], now: i64) -> Vec<usize> {
+    let max_age = policy.max_age_days.map(|d| d as i64 * MILLIS_PER_DAY);
+    let max_size = policy
+        .max_total_size_mb
+        .map(|mb| mb.saturating_mul(1024 * 1024));
+
+    let mut newest_per_agent = HashSet::new();
+    let mut kept = 0u32;
+    let mut kept_size = 0u64;
+    let mut selected = Vec::new();
+
+    for (i, file) in files.iter().enumerate() {
+        if newest_per_agent.insert(file.agent_id.clone()) {
+            kept += 1;
+            kept_size += file.size_bytes;
+            continue;
+        }
+
+        let too_old = max_age
+            .map(|age| now - file.modified > age)
+            .unwrap_or(false);
+        let over_count = policy
+            .keep_last_sessions
+            .map(|n| kept >= n)
+            .unwrap_or(false);
+        let over_size = max_size
+            .map(|max| kept_size.saturating_add(file.size_bytes) > max)
+            .unwrap_or(false);
+
+        if too_old || over_count || over_size {
+            selected.push(i);
+        } else {
+            kept += 1;
+            kept_size += file.size_bytes;
+        }
+    }
+    selected
+}
+
+/// Daily memory logs (and other memory files) older than the max age
+fn select_memory(policy: &RetentionPolicy, config: &Config, now: i64) -> Vec<Candidate> {
+    let Some(max_age_days) = policy.max_age_days else {
+        return Vec::new();
+    };
+    let today = now / MILLIS_PER_DAY;
+
+    let mut selected = Vec::new();
+    for workspace_dir in crate::workspace::all_workspace_dirs(config) {
+        let dir = workspace_dir.join("memory");
+        let Ok(files) = crate::memory::list_files(&dir) else {
+            continue;
+        };
+        for file in files {
+            let age_days = match file.date.as_deref().and_then(parse_date_days) {
+                Some(days) => today - days,
+                None => (now - file.modified) / MILLIS_PER_DAY,
+            };
+            if age_days > max_age_days as i64 {
+                let path = dir.join(&file.name);
+                selected.push(Candidate {
+                    archive_name: archive_name(&path),
+                    size: file.size_bytes,
+                    path,
+                });
+            }
+        }
+    }
+    selected
+}
+
+/// Drop pruned sessions from their sessions.json so OpenClaw doesn't list them
+fn remove_from_session_store(pruned: &[&TranscriptFile]) {
+    let mut by_dir: Vec<(PathBuf, HashSet<String>)> = Vec::new();
+    for file in pruned {
+        let Some(dir) = file.path.parent() else {
+            continue;
+        };
+        match by_dir.iter_mut().find(|(d, _)| d == dir) {
+            Some((_, ids)) => {
+                ids.insert(file.session_id.clone());
+            }
+            None => by_dir.push((dir.to_path_buf(), HashSet::from([file.session_id.clone()]))),
+        }
+    }
+
+    for (dir, ids) in by_dir {
+        let store = dir.join("sessions.json");
+        let Ok(contents) = fs::read_to_string(&store) else {
+            continue;
+        };
+        let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(&contents) else {
+            continue;
+        };
+        let before = map.len();
+        map.retain(|_, entry| {
+            entry
+                .get("sessionId")
+                .and_then(Value::as_str)
+                .map(|id| !ids.contains(id))
+                .unwrap_or(true)
+        });
+        if map.len() == before {
+            continue;
+        }
+        match serde_json::to_string_pretty(&Value::Object(map)) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&store, json) {
+                    eprintln!("[retention] Failed to update {:?}: {}", store, e);
+                }
+            }
+            Err(e) => eprintln!("[retention] Failed to update {:?}: {}", store, e),
+        }
+    }
+}
+
+fn archive_dir(policy: &RetentionPolicy) -> Option<PathBuf> {
+    match policy.archive_dir.as_deref().filter(|d| !d.is_empty()) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => get_data_app_dir().map(|d| d.join("archive")),
+    }
+}
+
+/// Create a new archive file. Existing files are never opened, so a manual
+/// run and the background job can't overwrite each other's archive.
+fn create_archive_file(dir: &Path) -> Result<(PathBuf, fs::File), AppError> {
+    let stamp = now_millis();
+    for n in 0..100 {
+        let name = match n {
+            0 => format!("simplestclaw-retention-{}.zip", stamp),
+            n => format!("simplestclaw-retention-{}-{}.zip", stamp, n),
+        };
+        let dest = dir.join(name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&dest)
+        {
+            Ok(file) => return Ok((dest, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(AppError::io(format!("Failed to create {:?}", dest), e)),
+        }
+    }
+    Err(AppError::new(
+        ErrorCode::AlreadyExists,
+        format!("Failed to create a new archive in {:?}", dir),
+    ))
+}
+
+/// Write every candidate into a new zip archive. Nothing is deleted here.
+fn write_archive(dir: &Path, candidates: &[Candidate]) -> Result<PathBuf, AppError> {
+    fs::create_dir_all(dir).map_err(|e| AppError::io("Failed to create archive dir", e))?;
+    let (dest, file) = create_archive_file(dir)?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+
+    let result = (|| {
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut added = HashSet::new();
+        for candidate in candidates {
+            // Two workspaces can point at the same directory
+            if !added.insert(candidate.archive_name.as_str()) {
+                continue;
+            }
+            let mut input = fs::File::open(&candidate.path)
+                .map_err(|e| AppError::io(format!("Failed to read {:?}", candidate.path), e))?;
+            zip.start_file(candidate.archive_name.as_str(), options)
+                .map_err(|e| {
+                    AppError::zip(format!("Failed to add {}", candidate.archive_name), e)
+                })?;
+            loop {
+                let n = input
+                    .read(&mut buf)
+                    .map_err(|e| AppError::io(format!("Failed to read {:?}", candidate.path), e))?;
+                if n == 0 {
+                    break;
+                }
+                zip.write_all(&buf[..n]).map_err(|e| {
+                    AppError::io(format!("Failed to add {}", candidate.archive_name), e)
+                })?;
+            }
+        }
+        zip.finish()
+            .map_err(|e| AppError::zip("Failed to finish archive", e))?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&dest);
+        return Err(e);
+    }
+    Ok(dest)
+}
+
+/// Apply `policy` to transcripts and memory. Blocking.
+pub fn apply(policy: &RetentionPolicy, dry_run: bool) -> Result<RetentionReport, AppError> {
+    let config = Config::load()?;
+    let now = now_millis();
+
+    let transcripts = crate::transcripts::transcript_files();
+    let pruned_sessions: Vec<&TranscriptFile> = select_sessions(policy, &transcripts, now)
+        .into_iter()
+        .map(|i| &transcripts[i])
+        .collect();
+    let memory = select_memory(policy, &config, now);
+
+    let session_candidates = pruned_sessions.iter().map(|f| Candidate {
+        path: f.path.clone(),
+        size: f.size_bytes,
+        archive_name: archive_name(&f.path),
+    });
+    let candidates: Vec<Candidate> = session_candidates.chain(memory).collect();
+
+    let mut report = RetentionReport {
+        dry_run,
+        sessions: pruned_sessions
+            .iter()
+            .map(|f| f.session_id.clone())
+            .collect(),
+        memory_files: Vec::new(),
+        bytes_freed: candidates.iter().map(|c| c.size).sum(),
+        archive_path: None,
+    };
+    report.memory_files = candidates[pruned_sessions.len()..]
+        .iter()
+        .map(|c| c.path.to_string_lossy().to_string())
+        .collect();
+
+    if dry_run || candidates.is_empty() {
+        return Ok(report);
+    }
+
+    // Archive first; if that fails, nothing is deleted
+    if policy.archive {
+        let dir = archive_dir(policy).ok_or_else(|| {
+            AppError::new(
+                ErrorCode::ConfigUnavailable,
+                "Could not determine archive directory",
+            )
+        })?;
+        let dest = write_archive(&dir, &candidates)?;
+        report.archive_path = Some(dest.to_string_lossy().to_string());
+    }
+
+    for candidate in &candidates {
+        if let Err(e) = fs::remove_file(&candidate.path) {
+            eprintln!("[retention] Failed to delete {:?}: {}", candidate.path, e);
+        }
+    }
+    remove_from_session_store(&pruned_sessions);
+
+    println!(
+        "[retention] Pruned {} session(s) and {} memory file(s), {} freed",
+        report.sessions.len(),
+        report.memory_files.len(),
+        format_bytes(report.bytes_freed)
+    );
+    Ok(report)
+}
+
+fn log_report(activity: &ActivityManager, report: &RetentionReport) {
+    let action = if report.archive_path.is_some() {
+        "Archived"
+    } else {
+        "Pruned"
+    };
+    log_activity(
+        activity,
+        "file_write",
+        &format!(
+            "{} {} session(s) and {} memory file(s) per retention policy ({})",
+            action,
+            report.sessions.len(),
+            report.memory_files.len(),
+            format_bytes(report.bytes_freed)
+        ),
+        "success",
+        report.archive_path.as_deref(),
+    );
+}
+
+/// Background job entry point: apply the configured policy if it's enabled
+pub async fn run_scheduled(app: &AppHandle) {
+    let policy = match Config::load() {
+        Ok(config) if config.retention.enabled => config.retention,
+        Ok(_) => return,
+        Err(e) => {
+            eprintln!("[retention] Failed to load config: {}", e);
+            return;
+        }
+    };
+
+    let result = tokio::task::spawn_blocking(move || apply(&policy, false)).await;
+    match result {
+        Ok(Ok(report)) if !report.is_empty() => {
+            if let Some(activity) = app.try_state::<ActivityManager>() {
+                log_report(&activity, &report);
+            }
+        }
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => {
+            eprintln!("[retention] Failed to apply policy: {}", e);
+            if let Some(activity) = app.try_state::<ActivityManager>() {
+                log_activity(
+                    &activity,
+                    "file_write",
+                    &format!("Retention policy failed: {}", e),
+                    "error",
+                    None,
+                );
+            }
+        }
+        Err(e) => eprintln!("[retention] Task failed: {}", e),
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn get_retention_policy() -> Result<RetentionPolicy, AppError> {
+    let config = Config::load()?;
+    Ok(config.retention)
+}
+
+#[tauri::command]
+pub fn set_retention_policy(policy: RetentionPolicy) -> Result<(), AppError> {
+    if policy.max_age_days == Some(0) {
+        return Err(AppError::invalid_input(
+            "Maximum age must be at least 1 day",
+        ));
+    }
+    if policy.max_total_size_mb == Some(0) {
+        return Err(AppError::invalid_input(
+            "Maximum size must be at least 1 MB",
+        ));
+    }
+    if policy.keep_last_sessions == Some(0) {
+        return Err(AppError::invalid_input("Keep at least 1 session"));
+    }
+    let mut config = Config::load()?;
+    config.retention = policy;
+    Ok(config.save()?)
+}
+
+/// Apply the saved policy now (even if the background job is disabled).
+/// With `dry_run` nothing is touched and the report lists what would be pruned.
+#[tauri::command]
+pub async fn apply_retention_policy(
+    app: AppHandle,
+    dry_run: bool,
+) -> Result<RetentionReport, AppError> {
+    let policy = Config::load()?.retention;
+    let report = tokio::task::spawn_blocking(move || apply(&policy, dry_run)).await??;
+
+    if !dry_run && !report.is_empty() {
+        if let Some(activity) = app.try_state::<ActivityManager>() {
+            log_report(&activity, &report);
+        }
+    }
+    Ok(report)
+}
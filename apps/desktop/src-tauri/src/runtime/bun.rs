@@ -0,0 +1,274 @@
+//! Bun as an alternative gateway host
+//!
+//! With Config.gateway_host set to "bun" the gateway runs via `bun x` instead
+//! of Node.js and npx, which starts noticeably faster on low-end machines.
+//! Bun is downloaded from its GitHub releases into the runtime directory and
+//! checked against the release's SHASUMS256.txt. Its signature isn't checked,
+//! even with Config.verify_node_signature, because Bun signs with its own key
+//! rather than a Node.js release key. A Bun already installed on
+//! the system is used instead when there is no managed one.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{checksum, download, extract, preflight, InstallJob, InstallPhase};
+use super::{RuntimeError, RuntimeManager, STAGING_PREFIX};
+use crate::sidecar::find_system_command;
+
+/// Bun release installed by the app
+const BUN_VERSION: &str = "1.3.6";
+
+/// Oldest Bun that runs the gateway
+pub const MIN_BUN: Version = Version::new(1, 2, 0);
+
+const BUN_RELEASES: &str = "https://github.com/oven-sh/bun/releases/download";
+
+/// Bun downloads go here rather than to the Node.js download file, so a
+/// partial Node.js download isn't thrown away
+const DOWNLOAD_TEMP_FILE: &str = "bun-download.tmp";
+
+/// Bun is a single binary of roughly 100MB
+const EXPANDED_BYTES: u64 = 160 * 1024 * 1024;
+
+/// A Bun the gateway can run on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BunRuntime {
+    pub path: String,
+    /// e.g. "1.3.6"; None if `bun --version` failed
+    pub version: Option<String>,
+    /// Whether the version meets MIN_BUN
+    pub compatible: bool,
+    /// Installed by the app rather than found on the system
+    pub managed: bool,
+}
+
+/// Bun's name for this build target
+fn bun_platform() -> Option<&'static str> {
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    return Some("darwin-aarch64");
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    return Some("darwin-x64");
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    return Some("linux-x64");
+
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    return Some("linux-aarch64");
+
+    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+    return Some("windows-x64");
+
+    #[cfg(not(any(
+        all(target_os = "macos", target_arch = "aarch64"),
+        all(target_os = "macos", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "aarch64"),
+        all(target_os = "windows", target_arch = "x86_64"),
+    )))]
+    return None;
+}
+
+/// Top-level folder inside the release zip, e.g. "bun-linux-x64"
+fn archive_root() -> Option<String> {
+    Some(format!("bun-{}", bun_platform()?))
+}
+
+fn archive_url() -> Option<String> {
+    Some(format!(
+        "{}/bun-v{}/{}.zip",
+        BUN_RELEASES,
+        BUN_VERSION,
+        archive_root()?
+    ))
+}
+
+/// Folder in the runtime directory, e.g. "bun-v1.3.6-linux-x64"
+fn folder_name() -> Option<String> {
+    Some(format!("bun-v{}-{}", BUN_VERSION, bun_platform()?))
+}
+
+fn bun_binary(folder: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return folder.join("bun.exe");
+
+    #[cfg(not(target_os = "windows"))]
+    return folder.join("bun");
+}
+
+/// The Bun installed by the app, if any
+pub fn managed_path() -> Option<PathBuf> {
+    let bun = bun_binary(&RuntimeManager::runtime_dir()?.join(folder_name()?));
+    bun.exists().then_some(bun)
+}
+
+/// Path of the Bun the gateway would use, without running it
+pub fn path() -> Option<String> {
+    managed_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .or_else(|| find_system_command("bun"))
+}
+
+fn probe_version(bun: &str) -> Option<Version> {
+    let output = Command::new(bun).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Version::parse(String::from_utf8_lossy(&output.stdout).trim()).ok()
+}
+
+/// The Bun the gateway would use, with its version checked
+pub fn find() -> Option<BunRuntime> {
+    let managed = managed_path().is_some();
+    let path = path()?;
+    let version = probe_version(&path);
+    Some(BunRuntime {
+        path,
+        managed,
+        compatible: version.as_ref().is_some_and(|v| *v >= MIN_BUN),
+        version: version.map(|v| v.to_string()),
+    })
+}
+
+/// Download, verify and install Bun into `runtime_dir`
+pub async fn install(
+    client: &reqwest::Client,
+    runtime_dir: &Path,
+    job: &InstallJob,
+) -> Result<(), RuntimeError> {
+    let url = archive_url().ok_or(RuntimeError::UnsupportedPlatform)?;
+    let root = archive_root().ok_or(RuntimeError::UnsupportedPlatform)?;
+    let folder_name = folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+
+    let temp_file = runtime_dir.join(DOWNLOAD_TEMP_FILE);
+    let archive_size = job
+        .cancel
+        .run(async { Ok(preflight::archive_size(client, &url).await) })
+        .await?;
+    preflight::check_dir(runtime_dir, archive_size + EXPANDED_BYTES)?;
+
+    // Checksum only: Bun signs SHASUMS256.txt with its own key, which isn't
+    // pinned here, so verify_node_signature doesn't apply
+    let file_name = checksum::archive_file_name(&url);
+    let expected_sha256 = job
+        .cancel
+        .run(checksum::fetch_expected_sha256(client, &url, false))
+        .await?;
+
+    println!("[runtime] Downloading Bun from {}", url);
+    job.progress.phase(InstallPhase::Downloading);
+    let download = download::download_file(
+        client,
+        &url,
+        &temp_file,
+        |downloaded, total| job.progress.downloaded(downloaded, total),
+        &job.cancel,
+    )
+    .await;
+    let actual_sha256 = match download {
+        Ok(sha256) => sha256,
+        Err(e) => {
+            if matches!(e, RuntimeError::Cancelled) {
+                download::discard(&temp_file);
+            }
+            return Err(e);
+        }
+    };
+
+    job.progress.phase(InstallPhase::Verifying);
+    if actual_sha256 != expected_sha256 {
+        download::discard(&temp_file);
+        return Err(RuntimeError::ChecksumMismatch {
+            file: file_name,
+            expected: expected_sha256,
+            actual: actual_sha256,
+        });
+    }
+
+    let staging_dir = runtime_dir.join(format!("{}{}", STAGING_PREFIX, folder_name));
+    let result = stage(&temp_file, &root, &staging_dir, job).await;
+    let result = match result.and_then(|staged| job.cancel.check().map(|_| staged)) {
+        Ok(staged) => RuntimeManager::promote(&staged, &runtime_dir.join(&folder_name)).await,
+        Err(e) => Err(e),
+    };
+    let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+    let _ = tokio::fs::remove_file(&temp_file).await;
+    result?;
+
+    println!("[runtime] Bun {} installed successfully", BUN_VERSION);
+    remove_old_versions(runtime_dir, &folder_name);
+    Ok(())
+}
+
+/// Extract the release zip into `staging_dir` and check the binary runs;
+/// returns the staged folder
+async fn stage(
+    archive: &Path,
+    root: &str,
+    staging_dir: &Path,
+    job: &InstallJob,
+) -> Result<PathBuf, RuntimeError> {
+    if staging_dir.exists() {
+        tokio::fs::remove_dir_all(staging_dir).await?;
+    }
+    tokio::fs::create_dir_all(staging_dir).await?;
+
+    job.progress.phase(InstallPhase::Extracting);
+    let (archive, dest, extract_root, extract_job) = (
+        archive.to_path_buf(),
+        staging_dir.to_path_buf(),
+        root.to_string(),
+        job.clone(),
+    );
+    tokio::task::spawn_blocking(move || {
+        extract::extract(&archive, &dest, &extract_root, &extract_job)
+    })
+    .await
+    .map_err(|e| RuntimeError::Extract(format!("Task error: {}", e)))??;
+
+    job.progress.phase(InstallPhase::Finalizing);
+    let staged = staging_dir.join(root);
+    let bun = bun_binary(&staged);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&bun, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let version = probe_version(&bun.to_string_lossy());
+    if version.as_ref().map(|v| v.to_string()).as_deref() != Some(BUN_VERSION) {
+        return Err(RuntimeError::Verification(format!(
+            "bun --version reported {:?}, expected {}",
+            version.map(|v| v.to_string()),
+            BUN_VERSION
+        )));
+    }
+    Ok(staged)
+}
+
+/// Remove Bun versions other than `current`
+fn remove_old_versions(runtime_dir: &Path, current: &str) {
+    let entries = match std::fs::read_dir(runtime_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with("bun-v") && name != current && entry.path().is_dir() {
+            println!("[runtime] Removing old Bun version: {}", name);
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Leftover download from an interrupted install that can't be resumed
+pub fn cleanup_interrupted_download(runtime_dir: &Path) {
+    let temp_file = runtime_dir.join(DOWNLOAD_TEMP_FILE);
+    if temp_file.exists() && !download::is_resumable(&temp_file) {
+        println!("[runtime] Removing leftover {}", DOWNLOAD_TEMP_FILE);
+        download::discard(&temp_file);
+    }
+}
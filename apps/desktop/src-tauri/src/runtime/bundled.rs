@@ -0,0 +1,66 @@
+//! Node.js runtime shipped inside the app bundle
+//!
+//! Builds with the `bundled-node` feature carry a Node.js release as a Tauri
+//! resource (see tauri.bundled-node.conf.json and `pnpm bundle:node`), so
+//! air-gapped machines get a runtime on first launch without any network
+//! access. The resource's `node/` folder holds either the release archive
+//! with its SHASUMS256.txt, which is verified and extracted like an imported
+//! archive, or an already extracted version folder, which is symlinked into
+//! the runtime directory.
+
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use super::{archive_name_for, folder_name_for, node_binary, NODE_VERSION};
+
+/// Folder under the resource directory that holds the bundled runtime
+const RESOURCE_FOLDER: &str = "node";
+
+/// A runtime found in the app bundle
+pub enum Bundled {
+    /// Release archive, with SHASUMS256.txt next to it
+    Archive(PathBuf),
+    /// Extracted version folder
+    #[cfg(unix)]
+    Folder(PathBuf),
+}
+
+/// The bundled runtime, if any. Bundles hold the compiled-in NODE_VERSION,
+/// so a version resolved from the release index doesn't hide them.
+pub fn find(app: &AppHandle) -> Option<Bundled> {
+    let dir = app.path().resource_dir().ok()?.join(RESOURCE_FOLDER);
+
+    // Symlinks to a folder need extra privileges on Windows
+    #[cfg(unix)]
+    {
+        let folder = dir.join(folder_name_for(NODE_VERSION)?);
+        if node_binary(&folder).is_file() {
+            return Some(Bundled::Folder(folder));
+        }
+    }
+
+    let archive = dir.join(archive_name_for(NODE_VERSION)?);
+    match archive.is_file() && dir.join("SHASUMS256.txt").is_file() {
+        true => Some(Bundled::Archive(archive)),
+        false => None,
+    }
+}
+
+/// Check a bundled version folder and link it to `link`
+#[cfg(unix)]
+pub async fn link_folder(
+    folder: &std::path::Path,
+    link: &std::path::Path,
+) -> Result<(), super::RuntimeError> {
+    let node = node_binary(folder);
+    let actual = super::doctor::node_version(&node).await?;
+    let expected = format!("v{}", NODE_VERSION);
+    if actual != expected {
+        return Err(super::RuntimeError::Verification(format!(
+            "bundled node reported {:?}, expected {}",
+            actual, expected
+        )));
+    }
+    std::os::unix::fs::symlink(folder, link)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Cooperative cancellation for runtime installs
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+use super::RuntimeError;
+
+/// Shared between an install and whoever may cancel it; clones share one flag
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Err(Cancelled) once cancelled, for `?` between install steps
+    pub fn check(&self) -> Result<(), RuntimeError> {
+        match self.is_cancelled() {
+            true => Err(RuntimeError::Cancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// Resolves when the token is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            // Register before checking the flag so a cancel in between isn't missed
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `future` unless cancelled first
+    pub async fn run<T>(
+        &self,
+        future: impl std::future::Future<Output = Result<T, RuntimeError>>,
+    ) -> Result<T, RuntimeError> {
+        tokio::select! {
+            result = future => result,
+            _ = self.cancelled() => Err(RuntimeError::Cancelled),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Node.js release integrity checks
+//!
+//! Every Node.js release publishes `SHASUMS256.txt` next to its archives, and
+//! a detached GPG signature of it (`SHASUMS256.txt.sig`) made by one of the
+//! release keys listed in the nodejs/node README. Signature checking is
+//! optional because it needs `gpg` on PATH.
+
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use super::{RuntimeError, RuntimeManager};
+
+/// Fingerprints of the Node.js release keys (nodejs/node README, "Release keys").
+/// A signature is only accepted if it was made by one of these.
+const NODE_RELEASE_KEYS: &[&str] = &[
+    "C0D6248439F1D5604AAFFB4021D900FFDB233756", // Antoine du Hamel
+    "DD792F5973C6DE52C432CBDAC77ABFA00DDBF2B7", // Juan José Arboleda
+    "CC68F5A3106FF448322E48ED27F5E38D5B0A215F", // Marco Ippolito
+    "8FCCA13FEF1D0C2E91008E09770F7A9A5AE15600", // Michaël Zasso
+    "890C08DB8579162FEE0DF9DB8BEAB4DFCF555EF4", // Rafael Gonzaga
+    "C82FA3AE1CBEDC6BE46B9360C43CEC45C17AB93C", // Richard Lau
+    "108F52B48DB57BB0CC439B2997B01419BD92F80A", // Ruy Adorno
+    "A363A499291CBBC940DD62E41F10027AF002F8B0", // Ulises Gascón
+];
+
+/// SHASUMS256.txt and its signature are small, so this covers the whole request
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Keys are fetched by fingerprint, so the keyserver doesn't need to be trusted
+const KEYSERVER: &str = "hkps://keys.openpgp.org";
+
+/// File name of the archive, i.e. the last segment of its URL
+pub fn archive_file_name(archive_url: &str) -> String {
+    archive_url
+        .rsplit('/')
+        .next()
+        .unwrap_or(archive_url)
+        .to_string()
+}
+
+/// SHASUMS256.txt lives in the same directory as the archive
+fn shasums_url(archive_url: &str) -> String {
+    match archive_url.rsplit_once('/') {
+        Some((base, _)) => format!("{}/SHASUMS256.txt", base),
+        None => "SHASUMS256.txt".to_string(),
+    }
+}
+
+/// Find the hash for `file_name` in SHASUMS256.txt ("<sha256>  <file name>" per line)
+pub fn parse_shasums(shasums: &str, file_name: &str) -> Option<String> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?;
+        let is_hash = hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit());
+        (name == file_name && is_hash).then(|| hash.to_lowercase())
+    })
+}
+
+/// Hash the first `len` bytes of a file (all of it with `u64::MAX`)
+pub fn hash_file_prefix(path: &Path, len: u64) -> Result<Sha256, RuntimeError> {
+    let mut hasher = Sha256::new();
+    let mut file = std::fs::File::open(path)?.take(len);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher)
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, RuntimeError> {
+    Ok(hex::encode(hash_file_prefix(path, u64::MAX)?.finalize()))
+}
+
+async fn fetch_bytes(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, RuntimeError> {
+    let response = client
+        .get(url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| RuntimeError::Download(format!("{}: {}", url, e)))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| RuntimeError::Download(format!("{}: {}", url, e)))?;
+    Ok(bytes.to_vec())
+}
+
+/// Expected hash of `file_name` from SHASUMS256.txt, checking its signature
+/// first when one is given
+async fn expected_sha256(
+    shasums: Vec<u8>,
+    signature: Option<Vec<u8>>,
+    file_name: String,
+) -> Result<String, RuntimeError> {
+    if let Some(signature) = signature {
+        let data = shasums.clone();
+        tokio::task::spawn_blocking(move || verify_shasums_signature(&data, &signature))
+            .await
+            .map_err(|e| RuntimeError::Signature(format!("Task error: {}", e)))??;
+        println!("[runtime] SHASUMS256.txt signature verified");
+    }
+
+    parse_shasums(&String::from_utf8_lossy(&shasums), &file_name)
+        .ok_or(RuntimeError::ChecksumNotFound(file_name))
+}
+
+/// Fetch SHASUMS256.txt for the release `archive_url` belongs to (checking its
+/// signature if asked to) and return the expected SHA-256 of the archive
+pub async fn fetch_expected_sha256(
+    client: &reqwest::Client,
+    archive_url: &str,
+    verify_signature: bool,
+) -> Result<String, RuntimeError> {
+    let url = shasums_url(archive_url);
+    let shasums = fetch_bytes(client, &url).await?;
+    let signature = match verify_signature {
+        true => Some(fetch_bytes(client, &format!("{}.sig", url)).await?),
+        false => None,
+    };
+    expected_sha256(shasums, signature, archive_file_name(archive_url)).await
+}
+
+/// Expected SHA-256 of a local archive from a SHASUMS256.txt saved next to it
+/// (for offline imports). None if there is no such file.
+pub async fn local_expected_sha256(
+    archive_path: &Path,
+    verify_signature: bool,
+) -> Option<Result<String, RuntimeError>> {
+    let dir = archive_path.parent()?;
+    let shasums = std::fs::read(dir.join("SHASUMS256.txt")).ok()?;
+    let signature = match verify_signature {
+        true => match std::fs::read(dir.join("SHASUMS256.txt.sig")) {
+            Ok(signature) => Some(signature),
+            Err(_) => {
+                return Some(Err(RuntimeError::Signature(
+                    "SHASUMS256.txt.sig not found next to the archive".to_string(),
+                )))
+            }
+        },
+        false => None,
+    };
+    let file_name = archive_path.file_name()?.to_string_lossy().to_string();
+    Some(expected_sha256(shasums, signature, file_name).await)
+}
+
+/// Run gpg against our own keyring (never the user's)
+fn gpg(home: &Path, args: &[&str]) -> Result<std::process::Output, RuntimeError> {
+    Command::new("gpg")
+        .arg("--homedir")
+        .arg(home)
+        .arg("--batch")
+        .arg("--no-tty")
+        .args(args)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                RuntimeError::Signature("gpg is not installed".to_string())
+            }
+            _ => RuntimeError::Signature(format!("Failed to run gpg: {}", e)),
+        })
+}
+
+/// Primary key fingerprint of a good signature, from gpg's status output.
+/// `VALIDSIG <sig fpr> <date> <ts> <expires> <version> <reserved> <pk algo>
+/// <hash algo> <class> <primary fpr>`
+fn valid_signer(status: &str) -> Option<String> {
+    status.lines().find_map(|line| {
+        let fields: Vec<&str> = line
+            .strip_prefix("[GNUPG:] VALIDSIG ")?
+            .split_whitespace()
+            .collect();
+        fields.last().map(|fpr| fpr.to_uppercase())
+    })
+}
+
+/// Check a detached signature of SHASUMS256.txt against the pinned release keys
+fn verify_shasums_signature(shasums: &[u8], signature: &[u8]) -> Result<(), RuntimeError> {
+    let home = RuntimeManager::runtime_dir()
+        .ok_or(RuntimeError::NoRuntimeDir)?
+        .join("gnupg");
+    std::fs::create_dir_all(&home)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&home, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    let data_path = home.join("SHASUMS256.txt");
+    let sig_path = home.join("SHASUMS256.txt.sig");
+    std::fs::write(&data_path, shasums)?;
+    std::fs::write(&sig_path, signature)?;
+    let data_arg = data_path.to_string_lossy().to_string();
+    let sig_arg = sig_path.to_string_lossy().to_string();
+    let verify_args = [
+        "--status-fd",
+        "1",
+        "--verify",
+        sig_arg.as_str(),
+        data_arg.as_str(),
+    ];
+
+    let mut output = gpg(&home, &verify_args)?;
+    let mut status = String::from_utf8_lossy(&output.stdout).to_string();
+
+    // First run (or a new release key): import the pinned keys and try again
+    if status.contains("NO_PUBKEY") {
+        println!(
+            "[runtime] Importing Node.js release keys from {}",
+            KEYSERVER
+        );
+        let mut import_args = vec!["--keyserver", KEYSERVER, "--recv-keys"];
+        import_args.extend(NODE_RELEASE_KEYS);
+        let import = gpg(&home, &import_args)?;
+        if !import.status.success() {
+            eprintln!(
+                "[runtime] gpg key import reported: {}",
+                String::from_utf8_lossy(&import.stderr).trim()
+            );
+        }
+        output = gpg(&home, &verify_args)?;
+        status = String::from_utf8_lossy(&output.stdout).to_string();
+    }
+
+    let _ = std::fs::remove_file(&data_path);
+    let _ = std::fs::remove_file(&sig_path);
+
+    match valid_signer(&status) {
+        Some(fpr) if NODE_RELEASE_KEYS.contains(&fpr.as_str()) => Ok(()),
+        Some(fpr) => Err(RuntimeError::Signature(format!(
+            "signed by {}, which is not a Node.js release key",
+            fpr
+        ))),
+        None => Err(RuntimeError::Signature(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )),
+    }
+}
@@ -0,0 +1,310 @@
+//! Runtime self-test
+//!
+//! `is_installed` only looks for files. These checks make sure the bundled
+//! Node.js actually works on this machine: the binary runs and reports the
+//! expected version, npx resolves to npm's CLI, the launchers are executable,
+//! the binary was built for this CPU, and its shared libraries are present.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::{node_binary, npx_binary, version, RuntimeError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// Not applicable on this platform, or blocked by an earlier failure
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeCheck {
+    /// Stable identifier, e.g. "version"
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: Option<String>,
+}
+
+/// Result of checking the bundled runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeReport {
+    /// True when no check failed
+    pub healthy: bool,
+    pub runtime_path: Option<String>,
+    pub checks: Vec<RuntimeCheck>,
+}
+
+fn check(id: &str, label: &str, result: Result<Option<String>, String>) -> RuntimeCheck {
+    let (status, detail) = match result {
+        Ok(detail) => (CheckStatus::Pass, detail),
+        Err(detail) => (CheckStatus::Fail, Some(detail)),
+    };
+    RuntimeCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        detail,
+    }
+}
+
+fn skipped(id: &str, label: &str, reason: &str) -> RuntimeCheck {
+    RuntimeCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status: CheckStatus::Skipped,
+        detail: Some(reason.to_string()),
+    }
+}
+
+/// Output of `node --version`, e.g. "v25.6.0"
+pub async fn node_version(node: &Path) -> Result<String, RuntimeError> {
+    let output = tokio::process::Command::new(node)
+        .arg("--version")
+        .output()
+        .await
+        .map_err(|e| RuntimeError::Verification(format!("Failed to run node: {}", e)))?;
+    if !output.status.success() {
+        return Err(RuntimeError::Verification(format!(
+            "node --version exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Where npm keeps npx-cli.js inside a Node.js folder
+fn npx_cli_path(folder: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return folder
+        .join("node_modules")
+        .join("npm")
+        .join("bin")
+        .join("npx-cli.js");
+
+    #[cfg(not(target_os = "windows"))]
+    return folder
+        .join("lib")
+        .join("node_modules")
+        .join("npm")
+        .join("bin")
+        .join("npx-cli.js");
+}
+
+fn check_npx_cli(folder: &Path) -> Result<Option<String>, String> {
+    let cli = npx_cli_path(folder);
+    if !cli.is_file() {
+        return Err(format!("{} is missing", cli.display()));
+    }
+
+    // On Unix bin/npx is a symlink to npx-cli.js; make sure it still points there
+    #[cfg(not(target_os = "windows"))]
+    {
+        let npx = npx_binary(folder);
+        let target = std::fs::canonicalize(&npx)
+            .map_err(|e| format!("{} does not resolve: {}", npx.display(), e))?;
+        let cli = std::fs::canonicalize(&cli).map_err(|e| e.to_string())?;
+        if target != cli {
+            return Err(format!(
+                "{} points to {} instead of npx-cli.js",
+                npx.display(),
+                target.display()
+            ));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn check_executable(folder: &Path) -> Result<Option<String>, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin_dir = folder.join("bin");
+    let mut not_executable = Vec::new();
+    for name in ["node", "npm", "npx"] {
+        // Follows symlinks, so npm/npx are judged by their JS entry points
+        let mode = std::fs::metadata(bin_dir.join(name))
+            .map_err(|e| format!("bin/{}: {}", name, e))?
+            .permissions()
+            .mode();
+        if mode & 0o111 == 0 {
+            not_executable.push(format!("bin/{}", name));
+        }
+    }
+    match not_executable.is_empty() {
+        true => Ok(None),
+        false => Err(format!("Not executable: {}", not_executable.join(", "))),
+    }
+}
+
+/// CPU architecture a binary was built for, from its ELF or Mach-O header
+#[cfg(not(target_os = "windows"))]
+fn binary_arch(header: &[u8]) -> Option<&'static str> {
+    if header.len() >= 20 && header.starts_with(b"\x7fELF") {
+        // e_machine; EI_DATA (byte 5) says which byte order it's stored in
+        let machine = match header[5] {
+            1 => u16::from_le_bytes([header[18], header[19]]),
+            _ => u16::from_be_bytes([header[18], header[19]]),
+        };
+        return match machine {
+            62 => Some("x86_64"),
+            183 => Some("aarch64"),
+            3 => Some("x86"),
+            40 => Some("arm"),
+            _ => Some("unknown"),
+        };
+    }
+    if header.len() >= 8 && header[..4] == [0xcf, 0xfa, 0xed, 0xfe] {
+        // 64-bit little-endian Mach-O; cputype follows the magic
+        return match u32::from_le_bytes([header[4], header[5], header[6], header[7]]) {
+            0x0100_0007 => Some("x86_64"),
+            0x0100_000c => Some("aarch64"),
+            _ => Some("unknown"),
+        };
+    }
+    None
+}
+
+#[cfg(not(target_os = "windows"))]
+fn check_arch(node: &Path) -> Result<Option<String>, String> {
+    use std::io::Read;
+
+    let mut header = [0u8; 20];
+    let mut file = std::fs::File::open(node).map_err(|e| e.to_string())?;
+    let read = file.read(&mut header).map_err(|e| e.to_string())?;
+    let arch = binary_arch(&header[..read]).ok_or("Not an ELF or Mach-O executable")?;
+    let host = std::env::consts::ARCH;
+    match arch == host {
+        true => Ok(Some(arch.to_string())),
+        false => Err(format!(
+            "node is built for {}, this machine is {}",
+            arch, host
+        )),
+    }
+}
+
+/// Libraries `ldd` reports as "libfoo.so.1 => not found"
+#[cfg(target_os = "linux")]
+fn missing_libraries(ldd_output: &str) -> Vec<String> {
+    ldd_output
+        .lines()
+        .filter(|line| line.contains("not found"))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|lib| lib.to_string())
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+async fn check_shared_libraries(node: &Path) -> RuntimeCheck {
+    const ID: &str = "sharedLibraries";
+    const LABEL: &str = "Shared libraries available";
+
+    let output = match tokio::process::Command::new("ldd").arg(node).output().await {
+        Ok(output) => output,
+        Err(_) => return skipped(ID, LABEL, "ldd is not available"),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let missing = missing_libraries(&stdout);
+    let result = match missing.is_empty() {
+        true => Ok(None),
+        false => Err(format!("Missing: {}", missing.join(", "))),
+    };
+    check(ID, LABEL, result)
+}
+
+/// Run every check against the installed version folder
+pub async fn run_checks(folder: &Path) -> RuntimeReport {
+    let mut checks = Vec::new();
+    let node = node_binary(folder);
+    let npx = npx_binary(folder);
+
+    let present = match (node.is_file(), npx.exists()) {
+        (true, true) => Ok(None),
+        (false, _) => Err(format!("{} is missing", node.display())),
+        (_, false) => Err(format!("{} is missing", npx.display())),
+    };
+    let installed = present.is_ok();
+    checks.push(check("installed", "node and npx present", present));
+
+    if !installed {
+        for (id, label) in [
+            ("version", "node runs and reports the expected version"),
+            ("npxCli", "npx resolves to npm's npx-cli.js"),
+            ("executable", "Binaries are executable"),
+            ("architecture", "Built for this CPU"),
+            ("sharedLibraries", "Shared libraries available"),
+        ] {
+            checks.push(skipped(id, label, "Runtime is not installed"));
+        }
+        return RuntimeReport {
+            healthy: false,
+            runtime_path: None,
+            checks,
+        };
+    }
+
+    let expected = format!("v{}", version::current_version());
+    let version = match node_version(&node).await {
+        Ok(v) if v == expected => Ok(Some(v)),
+        Ok(v) => Err(format!("Reported {}, expected {}", v, expected)),
+        Err(e) => Err(e.to_string()),
+    };
+    checks.push(check(
+        "version",
+        "node runs and reports the expected version",
+        version,
+    ));
+
+    checks.push(check(
+        "npxCli",
+        "npx resolves to npm's npx-cli.js",
+        check_npx_cli(folder),
+    ));
+
+    #[cfg(unix)]
+    checks.push(check(
+        "executable",
+        "Binaries are executable",
+        check_executable(folder),
+    ));
+    #[cfg(not(unix))]
+    checks.push(skipped(
+        "executable",
+        "Binaries are executable",
+        "Not applicable on Windows",
+    ));
+
+    #[cfg(not(target_os = "windows"))]
+    checks.push(check(
+        "architecture",
+        "Built for this CPU",
+        check_arch(&node),
+    ));
+    #[cfg(target_os = "windows")]
+    checks.push(skipped(
+        "architecture",
+        "Built for this CPU",
+        "Not checked on Windows",
+    ));
+
+    #[cfg(target_os = "linux")]
+    checks.push(check_shared_libraries(&node).await);
+    #[cfg(not(target_os = "linux"))]
+    checks.push(skipped(
+        "sharedLibraries",
+        "Shared libraries available",
+        "Only checked on Linux",
+    ));
+
+    RuntimeReport {
+        healthy: checks.iter().all(|c| c.status != CheckStatus::Fail),
+        runtime_path: Some(folder.to_string_lossy().to_string()),
+        checks,
+    }
+}
@@ -0,0 +1,521 @@
+//! Resumable downloads
+//!
+//! The runtime archive is ~50MB, which is a lot on a flaky connection. Partial
+//! downloads are kept on disk together with the server's ETag/Last-Modified,
+//! and later attempts ask for the rest with an HTTP Range request guarded by
+//! If-Range. If the file changed on the server it is sent in full instead and
+//! the download starts over.
+//!
+//! Failed attempts are retried with jittered exponential backoff, and a
+//! connection that doesn't answer or stops delivering data is treated as
+//! failed after a timeout.
+//!
+//! With Config.parallel_download the file is instead split into chunks that
+//! are fetched over several connections at once, which helps on links where
+//! latency rather than bandwidth limits a single stream. This needs a server
+//! that honours Range requests; otherwise it falls back to one connection.
+
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+
+use super::cancel::CancelToken;
+use super::{checksum, RuntimeError};
+
+const MAX_ATTEMPTS: u32 = 6;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// Give up on a connection that delivers no response headers or no body data
+/// for this long
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connections used by a parallel download
+const PARALLEL_CONNECTIONS: u64 = 4;
+
+/// Files smaller than this are downloaded over a single connection
+const MIN_PARALLEL_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Saved next to a partial download so it can be resumed safely
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PartialMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl PartialMeta {
+    /// Validator for If-Range; a strong ETag is preferred
+    fn validator(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|e| !e.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+fn meta_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_os_string();
+    name.push(".meta");
+    PathBuf::from(name)
+}
+
+fn load_meta(dest: &Path) -> Option<PartialMeta> {
+    let contents = std::fs::read_to_string(meta_path(dest)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn save_meta(dest: &Path, meta: &PartialMeta) -> Result<(), RuntimeError> {
+    let json = serde_json::to_string(meta).map_err(|e| RuntimeError::Download(e.to_string()))?;
+    std::fs::write(meta_path(dest), json)?;
+    Ok(())
+}
+
+/// Remove a partial download and its metadata
+pub fn discard(dest: &Path) {
+    let _ = std::fs::remove_file(dest);
+    let _ = std::fs::remove_file(meta_path(dest));
+}
+
+/// Whether `dest` is a partial download a later attempt can pick up from
+pub fn is_resumable(dest: &Path) -> bool {
+    load_meta(dest).is_some_and(|m| m.validator().is_some())
+}
+
+/// Whether another attempt might succeed
+fn is_retryable(error: &RuntimeError) -> bool {
+    match error {
+        RuntimeError::Http(status) => *status >= 500 || *status == 408 || *status == 429,
+        RuntimeError::Download(_) | RuntimeError::Stalled(_) => true,
+        _ => false,
+    }
+}
+
+/// Exponential backoff with "equal jitter": half fixed, half random
+fn backoff(attempt: u32) -> Duration {
+    let exp = BASE_DELAY
+        .saturating_mul(1u32 << attempt.min(10))
+        .min(MAX_DELAY);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let jitter = (nanos % 1000) as f64 / 1000.0;
+    exp / 2 + exp.mul_f64(jitter / 2.0)
+}
+
+/// Total size from "Content-Range: bytes 100-199/200"
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit('/').next()?.parse().ok()
+}
+
+/// Start of the range from "Content-Range: bytes 100-199/200"
+fn content_range_start(value: &str) -> Option<u64> {
+    value
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn header(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// Send `request`, giving up if the response headers don't arrive within
+/// STALL_TIMEOUT. The client only has a connect timeout, and the stall timer
+/// while streaming only starts once the body does.
+async fn send(
+    request: reqwest::RequestBuilder,
+    cancel: &CancelToken,
+) -> Result<reqwest::Response, RuntimeError> {
+    cancel
+        .run(async {
+            match tokio::time::timeout(STALL_TIMEOUT, request.send()).await {
+                Ok(result) => result.map_err(|e| RuntimeError::Download(e.to_string())),
+                Err(_) => Err(RuntimeError::Stalled(STALL_TIMEOUT.as_secs())),
+            }
+        })
+        .await
+}
+
+/// One download attempt, resuming from what's on disk when possible.
+/// Returns the SHA-256 of the complete file.
+async fn attempt(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    on_progress: &mut (dyn FnMut(u64, Option<u64>) + Send),
+    cancel: &CancelToken,
+) -> Result<String, RuntimeError> {
+    let meta = load_meta(dest).filter(|m| m.url == url);
+    let existing = std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    let resume_from = match meta.as_ref().and_then(|m| m.validator()) {
+        Some(_) if existing > 0 => existing,
+        _ => 0,
+    };
+
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        let validator = meta
+            .as_ref()
+            .and_then(|m| m.validator())
+            .unwrap_or_default();
+        request = request
+            .header(RANGE, format!("bytes={}-", resume_from))
+            .header(IF_RANGE, validator);
+    }
+    let response = send(request, cancel).await?;
+
+    let status = response.status();
+    let resuming = status == StatusCode::PARTIAL_CONTENT;
+    let range_start = header(&response, CONTENT_RANGE).and_then(|r| content_range_start(&r));
+    if resuming && (resume_from == 0 || range_start != Some(resume_from)) {
+        // Appending a range we didn't ask for would corrupt the file
+        discard(dest);
+        return Err(RuntimeError::Download(
+            "Server did not return the requested range".to_string(),
+        ));
+    }
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // Our partial file doesn't match the server's; start over next attempt
+        discard(dest);
+        return Err(RuntimeError::Download(
+            "Server rejected resume range".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        return Err(RuntimeError::Http(status.as_u16()));
+    }
+
+    let (mut file, mut hasher, mut downloaded, total) = if resuming {
+        println!("[runtime] Resuming download at {} bytes", resume_from);
+        let total = header(&response, CONTENT_RANGE).and_then(|r| content_range_total(&r));
+        let path = dest.to_path_buf();
+        let hasher =
+            tokio::task::spawn_blocking(move || checksum::hash_file_prefix(&path, resume_from))
+                .await
+                .map_err(|e| RuntimeError::Task(e.to_string()))??;
+        let file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(dest)
+            .await?;
+        (file, hasher, resume_from, total)
+    } else {
+        // Full response: the server ignored the range or the file changed
+        save_meta(
+            dest,
+            &PartialMeta {
+                url: url.to_string(),
+                etag: header(&response, ETAG),
+                last_modified: header(&response, LAST_MODIFIED),
+            },
+        )?;
+        let file = tokio::fs::File::create(dest).await?;
+        (file, Sha256::new(), 0, response.content_length())
+    };
+    on_progress(downloaded, total);
+
+    let mut stream = response.bytes_stream();
+    loop {
+        let next =
+            cancel.run(async { Ok(tokio::time::timeout(STALL_TIMEOUT, stream.next()).await) });
+        let chunk = match next.await? {
+            Err(_) => return Err(RuntimeError::Stalled(STALL_TIMEOUT.as_secs())),
+            Ok(None) => break,
+            Ok(Some(chunk)) => chunk.map_err(|e| RuntimeError::Download(e.to_string()))?,
+        };
+        file.write_all(&chunk).await?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+    file.flush().await?;
+
+    if let Some(total) = total {
+        if downloaded != total {
+            return Err(RuntimeError::Download(format!(
+                "Connection closed after {} of {} bytes",
+                downloaded, total
+            )));
+        }
+    }
+
+    let _ = std::fs::remove_file(meta_path(dest));
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Download `url` to `dest`, resuming and retrying as needed.
+/// `on_progress` gets (bytes downloaded, total bytes if known).
+/// Returns the SHA-256 of the downloaded file. On cancellation the partial
+/// file is left in place; callers decide whether to discard it.
+pub async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    mut on_progress: impl FnMut(u64, Option<u64>) + Send,
+    cancel: &CancelToken,
+) -> Result<String, RuntimeError> {
+    let mut attempt_no = 0;
+    loop {
+        match attempt(client, url, dest, &mut on_progress, cancel).await {
+            Ok(sha256) => return Ok(sha256),
+            Err(e) if is_retryable(&e) && attempt_no + 1 < MAX_ATTEMPTS => {
+                let delay = backoff(attempt_no);
+                attempt_no += 1;
+                eprintln!(
+                    "[runtime] Download attempt {} failed: {}. Retrying in {:.1}s",
+                    attempt_no,
+                    e,
+                    delay.as_secs_f32()
+                );
+                cancel
+                    .run(async {
+                        tokio::time::sleep(delay).await;
+                        Ok(())
+                    })
+                    .await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Ask for the first byte to learn whether the server serves ranges.
+/// Returns the file size and its If-Range validator, or None if ranges
+/// aren't supported.
+async fn probe_ranges(
+    client: &reqwest::Client,
+    url: &str,
+    cancel: &CancelToken,
+) -> Result<Option<(u64, Option<String>)>, RuntimeError> {
+    let response = send(client.get(url).header(RANGE, "bytes=0-0"), cancel).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(RuntimeError::Http(status.as_u16()));
+    }
+    if status != StatusCode::PARTIAL_CONTENT {
+        return Ok(None);
+    }
+    let total = match header(&response, CONTENT_RANGE).and_then(|r| content_range_total(&r)) {
+        Some(total) => total,
+        None => return Ok(None),
+    };
+    let meta = PartialMeta {
+        url: url.to_string(),
+        etag: header(&response, ETAG),
+        last_modified: header(&response, LAST_MODIFIED),
+    };
+    Ok(Some((total, meta.validator().map(|v| v.to_string()))))
+}
+
+/// What the chunks of a parallel download share
+struct ChunkedDownload<'a> {
+    client: &'a reqwest::Client,
+    url: &'a str,
+    dest: &'a Path,
+    /// ETag or Last-Modified the chunks must all come from
+    validator: Option<&'a str>,
+    cancel: &'a CancelToken,
+}
+
+/// One attempt at downloading `offset..end` of the file into the same bytes
+/// of `dest`. `offset` advances as data is written, so a retry picks up there.
+async fn range_attempt(
+    download: &ChunkedDownload<'_>,
+    offset: &mut u64,
+    end: u64,
+    progress: &mpsc::UnboundedSender<u64>,
+) -> Result<(), RuntimeError> {
+    let cancel = download.cancel;
+    let mut request = download
+        .client
+        .get(download.url)
+        .header(RANGE, format!("bytes={}-{}", *offset, end - 1));
+    if let Some(validator) = download.validator {
+        request = request.header(IF_RANGE, validator);
+    }
+    let response = send(request, cancel).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(RuntimeError::Http(status.as_u16()));
+    }
+    // A full response means the range was ignored or the file changed
+    let in_range = status == StatusCode::PARTIAL_CONTENT
+        && header(&response, CONTENT_RANGE).and_then(|r| content_range_start(&r)) == Some(*offset);
+    if !in_range {
+        return Err(RuntimeError::Download(
+            "Server did not return the requested range".to_string(),
+        ));
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(download.dest)
+        .await?;
+    file.seek(std::io::SeekFrom::Start(*offset)).await?;
+
+    let mut stream = response.bytes_stream();
+    loop {
+        let next =
+            cancel.run(async { Ok(tokio::time::timeout(STALL_TIMEOUT, stream.next()).await) });
+        let chunk = match next.await? {
+            Err(_) => return Err(RuntimeError::Stalled(STALL_TIMEOUT.as_secs())),
+            Ok(None) => break,
+            Ok(Some(chunk)) => chunk.map_err(|e| RuntimeError::Download(e.to_string()))?,
+        };
+        if *offset + chunk.len() as u64 > end {
+            return Err(RuntimeError::Download(
+                "Server sent more than the requested range".to_string(),
+            ));
+        }
+        file.write_all(&chunk).await?;
+        *offset += chunk.len() as u64;
+        let _ = progress.send(chunk.len() as u64);
+    }
+    file.flush().await?;
+
+    match *offset == end {
+        true => Ok(()),
+        false => Err(RuntimeError::Download(format!(
+            "Connection closed {} bytes short of the chunk end",
+            end - *offset
+        ))),
+    }
+}
+
+/// Download one chunk, retrying like `download_file`
+async fn fetch_range(
+    download: &ChunkedDownload<'_>,
+    range: Range<u64>,
+    progress: mpsc::UnboundedSender<u64>,
+) -> Result<(), RuntimeError> {
+    let mut offset = range.start;
+    let mut attempt_no = 0;
+    loop {
+        match range_attempt(download, &mut offset, range.end, &progress).await {
+            Ok(()) => return Ok(()),
+            Err(e) if is_retryable(&e) && attempt_no + 1 < MAX_ATTEMPTS => {
+                let delay = backoff(attempt_no);
+                attempt_no += 1;
+                eprintln!(
+                    "[runtime] Chunk at {} failed: {}. Retrying in {:.1}s",
+                    offset,
+                    e,
+                    delay.as_secs_f32()
+                );
+                download
+                    .cancel
+                    .run(async {
+                        tokio::time::sleep(delay).await;
+                        Ok(())
+                    })
+                    .await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Download `url` to `dest` in chunks over several connections. Returns
+/// None without downloading anything if the server doesn't serve ranges or
+/// the file is too small to be worth splitting.
+async fn parallel_attempt(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    on_progress: &mut (dyn FnMut(u64, Option<u64>) + Send),
+    cancel: &CancelToken,
+) -> Result<Option<String>, RuntimeError> {
+    let (total, validator) = match probe_ranges(client, url, cancel).await? {
+        Some((total, validator)) if total >= MIN_PARALLEL_SIZE => (total, validator),
+        _ => return Ok(None),
+    };
+
+    // Chunks are written in place, so the file can't be resumed as a stream
+    discard(dest);
+    std::fs::File::create(dest)?.set_len(total)?;
+    println!(
+        "[runtime] Downloading {} bytes over {} connections",
+        total, PARALLEL_CONNECTIONS
+    );
+
+    let download = ChunkedDownload {
+        client,
+        url,
+        dest,
+        validator: validator.as_deref(),
+        cancel,
+    };
+    let chunk_size = total.div_ceil(PARALLEL_CONNECTIONS);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fetches = futures_util::future::try_join_all((0..PARALLEL_CONNECTIONS).map(|i| {
+        let start = i * chunk_size;
+        fetch_range(
+            &download,
+            start..(start + chunk_size).min(total),
+            tx.clone(),
+        )
+    }));
+    drop(tx);
+
+    // Progress arrives over the channel until every chunk is done or one fails
+    let report = async {
+        let mut downloaded = 0;
+        on_progress(downloaded, Some(total));
+        while let Some(bytes) = rx.recv().await {
+            downloaded += bytes;
+            on_progress(downloaded, Some(total));
+        }
+    };
+    let (result, ()) = tokio::join!(fetches, report);
+    result?;
+
+    let path = dest.to_path_buf();
+    let sha256 = tokio::task::spawn_blocking(move || checksum::sha256_file(&path))
+        .await
+        .map_err(|e| RuntimeError::Download(format!("Task error: {}", e)))??;
+    Ok(Some(sha256))
+}
+
+/// Like `download_file`, but fetches chunks over several connections when
+/// the server supports it. Falls back to a single connection when it
+/// doesn't, when a chunk keeps failing, or to finish a partial download.
+pub async fn download_file_parallel(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    mut on_progress: impl FnMut(u64, Option<u64>) + Send,
+    cancel: &CancelToken,
+) -> Result<String, RuntimeError> {
+    if is_resumable(dest) {
+        return download_file(client, url, dest, on_progress, cancel).await;
+    }
+    match parallel_attempt(client, url, dest, &mut on_progress, cancel).await {
+        Ok(Some(sha256)) => Ok(sha256),
+        Ok(None) => download_file(client, url, dest, on_progress, cancel).await,
+        Err(RuntimeError::Cancelled) => Err(RuntimeError::Cancelled),
+        Err(e) => {
+            eprintln!(
+                "[runtime] Parallel download failed ({}), retrying over a single connection",
+                e
+            );
+            discard(dest);
+            download_file(client, url, dest, on_progress, cancel).await
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! Archive extraction, one entry at a time
+//!
+//! Node.js ships .tar.xz, .tar.gz and .zip archives. The format is read from
+//! the file's magic bytes rather than its name, since imported archives can
+//! be renamed and mirrors don't always keep the extension.
+//!
+//! Unpacking entry by entry lets an install report progress by entry count
+//! and stop promptly when cancelled. Both functions block and are meant to
+//! run on `spawn_blocking`.
+//!
+//! Archives may come from a user-configured mirror or a file picked on disk,
+//! so every entry is checked before anything is written: paths must be
+//! relative, stay under the expected top-level folder and not use `..`;
+//! links must point inside that folder; and total size and entry count are
+//! capped so a hostile archive can't fill the disk. Link targets are only
+//! checked lexically, so a chain of links could still lead outside; tar's
+//! `unpack_in` canonicalizes each parent before writing, and .zip archives
+//! (Windows-only for Node.js) may not contain links at all.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+use super::{InstallJob, RuntimeError};
+
+/// A Node.js release has a few thousand entries
+const MAX_ENTRIES: u64 = 50_000;
+
+/// A Node.js release unpacks to roughly 200MB
+const MAX_TOTAL_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Why an archive was refused
+#[derive(Error, Debug)]
+pub enum UnsafeArchive {
+    #[error("absolute path {0}")]
+    AbsolutePath(String),
+    #[error("path with '..' {0}")]
+    ParentComponent(String),
+    #[error("{path} is outside the expected folder {root}")]
+    OutsideRoot { path: String, root: String },
+    #[error("link {path} points outside the archive: {target}")]
+    EscapingLink { path: String, target: String },
+    #[error("unsupported entry type for {0}")]
+    UnsupportedEntry(String),
+    #[error("more than {0} entries")]
+    TooManyEntries(u64),
+    #[error("more than {0} bytes uncompressed")]
+    TooLarge(u64),
+}
+
+fn extract_error(e: impl std::fmt::Display) -> RuntimeError {
+    RuntimeError::Extract(e.to_string())
+}
+
+/// Check an entry path and return it without `.` components. It must be
+/// relative, free of `..`, and inside `root` (the archive's top-level folder).
+fn checked_path(path: &Path, root: &str) -> Result<PathBuf, UnsafeArchive> {
+    let display = path.to_string_lossy().to_string();
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err(UnsafeArchive::ParentComponent(display)),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(UnsafeArchive::AbsolutePath(display))
+            }
+        }
+    }
+    match clean.components().next() {
+        Some(Component::Normal(first)) if first == root => Ok(clean),
+        _ => Err(UnsafeArchive::OutsideRoot {
+            path: display,
+            root: root.to_string(),
+        }),
+    }
+}
+
+/// Check that a symlink at `link` (already checked) pointing to `target`
+/// resolves inside `root`
+fn check_symlink(link: &Path, target: &Path, root: &str) -> Result<(), UnsafeArchive> {
+    let escaping = || UnsafeArchive::EscapingLink {
+        path: link.to_string_lossy().to_string(),
+        target: target.to_string_lossy().to_string(),
+    };
+
+    // Resolve lexically from the link's own directory
+    let mut resolved = link.parent().map(Path::to_path_buf).unwrap_or_default();
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return Err(escaping());
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return Err(escaping()),
+        }
+    }
+    match resolved.components().next() {
+        Some(Component::Normal(first)) if first == root => Ok(()),
+        _ => Err(escaping()),
+    }
+}
+
+/// Running totals checked against the caps
+#[derive(Default)]
+struct Budget {
+    bytes: u64,
+}
+
+impl Budget {
+    fn add(&mut self, bytes: u64) -> Result<(), UnsafeArchive> {
+        self.bytes = self.bytes.saturating_add(bytes);
+        match self.bytes > MAX_TOTAL_BYTES {
+            true => Err(UnsafeArchive::TooLarge(MAX_TOTAL_BYTES)),
+            false => Ok(()),
+        }
+    }
+
+    fn remaining(&self) -> u64 {
+        MAX_TOTAL_BYTES.saturating_sub(self.bytes)
+    }
+}
+
+fn check_entry_count(count: u64) -> Result<(), UnsafeArchive> {
+    match count > MAX_ENTRIES {
+        true => Err(UnsafeArchive::TooManyEntries(MAX_ENTRIES)),
+        false => Ok(()),
+    }
+}
+
+/// Archive formats Node.js releases come in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    TarXz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Identify an archive by its first bytes
+    pub fn detect(archive_path: &Path) -> Result<Self, RuntimeError> {
+        let mut magic = [0u8; 6];
+        let read = File::open(archive_path)?.read(&mut magic)?;
+        match &magic[..read] {
+            [0x1f, 0x8b, ..] => Ok(Self::TarGz),
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00] => Ok(Self::TarXz),
+            [b'P', b'K', 0x03, 0x04, ..] => Ok(Self::Zip),
+            _ => Err(RuntimeError::Extract(format!(
+                "{} is not a .tar.gz, .tar.xz or .zip archive",
+                archive_path.display()
+            ))),
+        }
+    }
+}
+
+/// Extract an archive whose entries all live under `root`
+pub fn extract(
+    archive_path: &Path,
+    dest: &Path,
+    root: &str,
+    job: &InstallJob,
+) -> Result<(), RuntimeError> {
+    match ArchiveFormat::detect(archive_path)? {
+        ArchiveFormat::Zip => extract_zip(archive_path, dest, root, job),
+        format => extract_tar(archive_path, format, dest, root, job),
+    }
+}
+
+fn open_tar(
+    archive_path: &Path,
+    format: ArchiveFormat,
+) -> Result<tar::Archive<Box<dyn Read>>, RuntimeError> {
+    let file = File::open(archive_path)?;
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::TarXz => Box::new(xz2::read::XzDecoder::new(file)),
+        _ => Box::new(flate2::read::GzDecoder::new(file)),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// Extract a compressed tarball whose entries all live under `root`
+fn extract_tar(
+    archive_path: &Path,
+    format: ArchiveFormat,
+    dest: &Path,
+    root: &str,
+    job: &InstallJob,
+) -> Result<(), RuntimeError> {
+    // A tarball has no index, so counting entries takes a pass of its own.
+    // Decompressing is fast next to writing the files out.
+    let mut archive = open_tar(archive_path, format)?;
+    let mut total = 0;
+    for entry in archive.entries().map_err(extract_error)? {
+        entry.map_err(extract_error)?;
+        total += 1;
+        check_entry_count(total)?;
+    }
+    job.progress.entries_total(total);
+
+    let mut budget = Budget::default();
+    let mut archive = open_tar(archive_path, format)?;
+    for entry in archive.entries().map_err(extract_error)? {
+        job.cancel.check()?;
+        let mut entry = entry.map_err(extract_error)?;
+        let path = entry.path().map_err(extract_error)?.into_owned();
+
+        use tar::EntryType;
+        match entry.header().entry_type() {
+            // Metadata for the following entries; nothing to write
+            EntryType::XGlobalHeader | EntryType::XHeader => {
+                job.progress.entry_extracted();
+                continue;
+            }
+            EntryType::Regular | EntryType::Continuous | EntryType::Directory => {
+                checked_path(&path, root)?;
+            }
+            EntryType::Symlink => {
+                let link = checked_path(&path, root)?;
+                let target = entry
+                    .link_name()
+                    .map_err(extract_error)?
+                    .ok_or_else(|| UnsafeArchive::UnsupportedEntry(link.display().to_string()))?;
+                check_symlink(&link, &target, root)?;
+            }
+            EntryType::Link => {
+                // Hard link targets are archive paths, like entry paths
+                checked_path(&path, root)?;
+                let target = entry
+                    .link_name()
+                    .map_err(extract_error)?
+                    .ok_or_else(|| UnsafeArchive::UnsupportedEntry(path.display().to_string()))?;
+                checked_path(&target, root)?;
+            }
+            _ => {
+                return Err(UnsafeArchive::UnsupportedEntry(path.display().to_string()).into());
+            }
+        }
+
+        budget.add(entry.header().size().map_err(extract_error)?)?;
+        entry.unpack_in(dest).map_err(extract_error)?;
+        job.progress.entry_extracted();
+    }
+    Ok(())
+}
+
+/// Check that `path`'s parent, once links are resolved, is still under `dest`
+fn ensure_inside(path: &Path, dest: &Path) -> Result<(), RuntimeError> {
+    let parent = path.parent().unwrap_or(dest).canonicalize()?;
+    match parent.starts_with(dest.canonicalize()?) {
+        true => Ok(()),
+        false => Err(UnsafeArchive::OutsideRoot {
+            path: path.display().to_string(),
+            root: dest.display().to_string(),
+        }
+        .into()),
+    }
+}
+
+/// Extract a .zip whose entries all live under `root`
+fn extract_zip(
+    archive_path: &Path,
+    dest: &Path,
+    root: &str,
+    job: &InstallJob,
+) -> Result<(), RuntimeError> {
+    let file = File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| RuntimeError::Extract(format!("Failed to read zip: {}", e)))?;
+    check_entry_count(archive.len() as u64)?;
+    job.progress.entries_total(archive.len() as u64);
+
+    let mut budget = Budget::default();
+    for i in 0..archive.len() {
+        job.cancel.check()?;
+        let mut entry = archive.by_index(i).map_err(extract_error)?;
+        unpack_zip_entry(&mut entry, dest, root, &mut budget)?;
+        job.progress.entry_extracted();
+    }
+    Ok(())
+}
+
+fn unpack_zip_entry(
+    entry: &mut zip::read::ZipFile<'_>,
+    dest: &Path,
+    root: &str,
+    budget: &mut Budget,
+) -> Result<(), RuntimeError> {
+    let relative = checked_path(Path::new(entry.name()), root)?;
+    let path = dest.join(&relative);
+
+    if entry.is_symlink() {
+        return Err(UnsafeArchive::UnsupportedEntry(relative.display().to_string()).into());
+    }
+    if entry.is_dir() {
+        std::fs::create_dir_all(&path)?;
+        ensure_inside(&path, dest)?;
+    } else {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        ensure_inside(&path, dest)?;
+        // The declared size can lie; never write more than the budget allows
+        let mut out = File::create(&path)?;
+        let limit = budget.remaining();
+        let written = std::io::copy(&mut entry.take(limit + 1), &mut out)?;
+        budget.add(written)?;
+    }
+
+    // Permission bits only; never setuid, setgid or sticky
+    #[cfg(unix)]
+    if let Some(mode) = entry.unix_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode & 0o777))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    /// A fresh, empty directory under the system temp dir
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "simplestclaw-extract-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Unpack every entry of an in-memory zip, stopping at the first error
+    fn unpack_zip(bytes: Vec<u8>, dest: &Path, root: &str) -> Result<(), RuntimeError> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut budget = Budget::default();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            unpack_zip_entry(&mut entry, dest, root, &mut budget)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn checked_path_accepts_paths_under_root() {
+        assert_eq!(
+            checked_path(Path::new("node/./bin/node"), "node").unwrap(),
+            PathBuf::from("node/bin/node")
+        );
+    }
+
+    #[test]
+    fn checked_path_rejects_escapes() {
+        assert!(matches!(
+            checked_path(Path::new("/etc/passwd"), "node"),
+            Err(UnsafeArchive::AbsolutePath(_))
+        ));
+        assert!(matches!(
+            checked_path(Path::new("node/../evil"), "node"),
+            Err(UnsafeArchive::ParentComponent(_))
+        ));
+        assert!(matches!(
+            checked_path(Path::new("other/bin/node"), "node"),
+            Err(UnsafeArchive::OutsideRoot { .. })
+        ));
+        assert!(matches!(
+            checked_path(Path::new("."), "node"),
+            Err(UnsafeArchive::OutsideRoot { .. })
+        ));
+    }
+
+    #[test]
+    fn check_symlink_resolves_from_the_link_directory() {
+        let link = Path::new("node/bin/npm");
+        assert!(check_symlink(link, Path::new("../lib/npm/bin/npm-cli.js"), "node").is_ok());
+        assert!(check_symlink(link, Path::new("../../other"), "node").is_err());
+        assert!(check_symlink(link, Path::new("../../../etc"), "node").is_err());
+        assert!(check_symlink(link, Path::new("/etc/passwd"), "node").is_err());
+    }
+
+    #[test]
+    fn zip_extracts_files_without_special_mode_bits() {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.add_directory("node/bin/", SimpleFileOptions::default())
+            .unwrap();
+        zip.start_file(
+            "node/bin/node",
+            SimpleFileOptions::default().unix_permissions(0o4755),
+        )
+        .unwrap();
+        zip.write_all(b"#!/bin/sh\n").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let dest = temp_dir("files");
+        unpack_zip(bytes, &dest, "node").unwrap();
+        let file = dest.join("node/bin/node");
+        assert_eq!(std::fs::read(&file).unwrap(), b"#!/bin/sh\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o7777, 0o755);
+        }
+        std::fs::remove_dir_all(&dest).unwrap();
+    }
+
+    /// `root/d/l1 -> ..` then `root/d/l1/l2 -> ../..` each pass the lexical
+    /// check, but together put `root/d/l1/l2/evil` outside `dest`
+    #[test]
+    fn zip_rejects_symlink_chains() {
+        assert!(check_symlink(Path::new("root/d/l1"), Path::new(".."), "root").is_ok());
+        assert!(check_symlink(Path::new("root/d/l1/l2"), Path::new("../.."), "root").is_ok());
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        zip.add_directory("root/d/", options).unwrap();
+        zip.add_symlink("root/d/l1", "..", options).unwrap();
+        zip.add_symlink("root/d/l1/l2", "../..", options).unwrap();
+        zip.start_file("root/d/l1/l2/evil", options).unwrap();
+        zip.write_all(b"evil").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let dest = temp_dir("chain");
+        let staging = dest.join("staging");
+        std::fs::create_dir_all(&staging).unwrap();
+        let result = unpack_zip(bytes, &staging, "root");
+        assert!(matches!(
+            result,
+            Err(RuntimeError::UnsafeArchive(
+                UnsafeArchive::UnsupportedEntry(_)
+            ))
+        ));
+        assert!(!dest.join("evil").exists());
+        assert!(!staging.join("root/d/l1").exists());
+        std::fs::remove_dir_all(&dest).unwrap();
+    }
+
+    /// Even if a link is already on disk, nothing is written through it
+    #[cfg(unix)]
+    #[test]
+    fn zip_never_writes_through_an_existing_link() {
+        let dest = temp_dir("existing-link");
+        let staging = dest.join("staging");
+        std::fs::create_dir_all(staging.join("root")).unwrap();
+        std::os::unix::fs::symlink(&dest, staging.join("root/out")).unwrap();
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file("root/out/evil", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"evil").unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let result = unpack_zip(bytes, &staging, "root");
+        assert!(matches!(
+            result,
+            Err(RuntimeError::UnsafeArchive(
+                UnsafeArchive::OutsideRoot { .. }
+            ))
+        ));
+        assert!(!dest.join("evil").exists());
+        std::fs::remove_dir_all(&dest).unwrap();
+    }
+}
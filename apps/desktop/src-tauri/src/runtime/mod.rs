@@ -0,0 +1,1149 @@
+//! Node.js Runtime Manager
+//!
+//! Downloads and manages a bundled Node.js runtime so users don't need
+//! to install Node.js separately. This makes the app work for "normal folk".
+//!
+//! The runtime is downloaded from official Node.js releases on first launch.
+//! Archives are checked against the release's SHASUMS256.txt before extraction.
+//! Builds with the `bundled-node` feature can ship the runtime inside the app
+//! and install it without network access (see bundled.rs).
+
+mod bun;
+#[cfg(feature = "bundled-node")]
+mod bundled;
+mod cancel;
+mod checksum;
+mod doctor;
+mod download;
+mod extract;
+mod network;
+mod preflight;
+mod progress;
+mod system;
+mod version;
+
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::AppHandle;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use crate::config::{Config, GatewayHost};
+use crate::error::AppError;
+pub use bun::{BunRuntime, MIN_BUN};
+use cancel::CancelToken;
+pub use doctor::RuntimeReport;
+pub use preflight::{check_dir, PreflightError};
+use progress::ProgressReporter;
+pub use system::{SystemNode, MIN_SYSTEM_NODE};
+
+#[derive(Error, Debug)]
+pub enum RuntimeError {
+    #[error("Unsupported platform")]
+    UnsupportedPlatform,
+    #[error("Could not determine runtime directory")]
+    NoRuntimeDir,
+    #[error("Download failed: {0}")]
+    Download(String),
+    #[error("Download failed: HTTP {0}")]
+    Http(u16),
+    #[error("Download stalled: no data received for {0} seconds")]
+    Stalled(u64),
+    #[error("Invalid network settings: {0}")]
+    Network(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Preflight(#[from] PreflightError),
+    #[error("No checksum for {0} in SHASUMS256.txt")]
+    ChecksumNotFound(String),
+    #[error("Checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("SHASUMS256.txt signature verification failed: {0}")]
+    Signature(String),
+    #[error("Failed to extract: {0}")]
+    Extract(String),
+    #[error("Refusing to extract unsafe archive: {0}")]
+    UnsafeArchive(#[from] extract::UnsafeArchive),
+    #[error("Installation verification failed: {0}")]
+    Verification(String),
+    #[error("Installation cancelled")]
+    Cancelled,
+    #[error("Another runtime installation is already in progress")]
+    Busy,
+    #[error("Install task failed: {0}")]
+    Task(String),
+    #[error("Invalid Node.js version constraint: {0}")]
+    VersionConstraint(String),
+    #[error("No Node.js release for this platform matches \"{0}\"")]
+    NoMatchingVersion(String),
+    #[error("Wrong archive for this platform: expected {expected}, got {actual}")]
+    WrongArchive { expected: String, actual: String },
+}
+
+/// Node.js version used until a version has been resolved from the release
+/// index (see version.rs), e.g. on a first launch without network access
+/// NOTE: Node 25+ is required to fix fetch timeout bugs in Node 22's undici implementation
+/// See: https://github.com/nodejs/undici/issues/3410
+const NODE_VERSION: &str = "25.6.0";
+
+/// Archives are downloaded to this file in the runtime directory
+const DOWNLOAD_TEMP_FILE: &str = "download.tmp";
+
+/// Archives are extracted into `<prefix><folder name>` and renamed into place
+const STAGING_PREFIX: &str = ".staging-";
+
+/// Node.js platform name, archive extension, and the build's name in the
+/// release index's `files` list for this build target. Unix builds use the
+/// .tar.xz archives, which are much smaller than the .tar.gz ones.
+fn node_platform() -> Option<(&'static str, &'static str, &'static str)> {
+    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+    return Some(("darwin-arm64", "tar.xz", "osx-arm64-tar"));
+
+    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
+    return Some(("darwin-x64", "tar.xz", "osx-x64-tar"));
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    return Some(("linux-x64", "tar.xz", "linux-x64"));
+
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    return Some(("linux-arm64", "tar.xz", "linux-arm64"));
+
+    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
+    return Some(("win-x64", "zip", "win-x64-zip"));
+
+    #[cfg(not(any(
+        all(target_os = "macos", target_arch = "aarch64"),
+        all(target_os = "macos", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "x86_64"),
+        all(target_os = "linux", target_arch = "aarch64"),
+        all(target_os = "windows", target_arch = "x86_64"),
+    )))]
+    return None;
+}
+
+/// Version folder for `version`, e.g. "node-v25.6.0-linux-x64". This is
+/// also the top-level folder inside that version's archives.
+fn folder_name_for(version: &str) -> Option<String> {
+    let (platform, _, _) = node_platform()?;
+    Some(format!("node-v{}-{}", version, platform))
+}
+
+/// The version in a folder name made by `folder_name_for`
+fn folder_version(folder_name: &str) -> Option<&str> {
+    let (platform, _, _) = node_platform()?;
+    folder_name
+        .strip_prefix("node-v")?
+        .strip_suffix(platform)?
+        .strip_suffix('-')
+}
+
+/// Version folder the runtime runs from
+fn node_folder_name() -> Option<String> {
+    folder_name_for(&version::current_version())
+}
+
+/// Version folder an install creates
+fn target_folder_name() -> Option<String> {
+    folder_name_for(&version::target_version())
+}
+
+/// Archive file name for `version` as published in the release directory
+fn archive_name_for(version: &str) -> Option<String> {
+    let (_, ext, _) = node_platform()?;
+    Some(format!("{}.{}", folder_name_for(version)?, ext))
+}
+
+/// Archive file name of the target version
+fn node_archive_name() -> Option<String> {
+    archive_name_for(&version::target_version())
+}
+
+/// Whether `name` is one of the release's archives for this platform, in
+/// any of the formats Node.js publishes
+fn is_node_archive_name(name: &str) -> bool {
+    let folder = match target_folder_name() {
+        Some(folder) => folder,
+        None => return false,
+    };
+    name.strip_prefix(folder.as_str())
+        .and_then(|ext| ext.strip_prefix('.'))
+        .is_some_and(|ext| ["tar.xz", "tar.gz", "zip"].contains(&ext))
+}
+
+/// URL of `file_name` in the target version's release directory under `mirror`
+fn node_release_url(mirror: &str, file_name: &str) -> String {
+    format!("{}/v{}/{}", mirror, version::target_version(), file_name)
+}
+
+/// Download URL for this platform's archive under `mirror`
+fn node_archive_url(mirror: &str) -> Option<String> {
+    Some(node_release_url(mirror, &node_archive_name()?))
+}
+
+/// node binary inside an extracted version folder
+fn node_binary(folder: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return folder.join("node.exe");
+
+    #[cfg(not(target_os = "windows"))]
+    return folder.join("bin").join("node");
+}
+
+/// npx launcher inside an extracted version folder
+fn npx_binary(folder: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    return folder.join("npx.cmd");
+
+    #[cfg(not(target_os = "windows"))]
+    return folder.join("bin").join("npx");
+}
+
+/// Runtime status for the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStatus {
+    pub installed: bool,
+    pub version: Option<String>,
+    pub node_path: Option<String>,
+    pub npx_path: Option<String>,
+    pub downloading: bool,
+    pub download_progress: f32,
+    /// Why the last install failed
+    pub error: Option<AppError>,
+    /// System Node.js the gateway runs on instead of the bundled runtime
+    pub system_node: Option<SystemNode>,
+}
+
+/// Install step reported in progress events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallPhase {
+    Downloading,
+    /// Checking the archive against SHASUMS256.txt
+    Verifying,
+    Extracting,
+    /// Checking the extracted binaries and moving them into place
+    Finalizing,
+}
+
+/// Download progress for the frontend, sent as `runtime-progress` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub phase: InstallPhase,
+    pub bytes_downloaded: u64,
+    pub total_bytes: Option<u64>,
+    pub bytes_per_sec: Option<u64>,
+    pub eta_secs: Option<u64>,
+    pub entries_extracted: u64,
+    pub total_entries: Option<u64>,
+    /// Progress of the current phase
+    pub percent: f32,
+}
+
+/// State for tracking downloads
+pub struct RuntimeState {
+    pub downloading: bool,
+    pub progress: f32,
+    pub error: Option<AppError>,
+}
+
+impl Default for RuntimeState {
+    fn default() -> Self {
+        Self {
+            downloading: false,
+            progress: 0.0,
+            error: None,
+        }
+    }
+}
+
+/// Result of an install, shared by everyone awaiting it
+pub type InstallResult = Result<(), Arc<RuntimeError>>;
+
+/// What a running install needs besides its inputs
+#[derive(Clone)]
+pub struct InstallJob {
+    pub cancel: CancelToken,
+    pub progress: ProgressReporter,
+}
+
+/// The install currently running, if any
+struct InstallFlight {
+    future: Shared<BoxFuture<'static, InstallResult>>,
+    cancel: CancelToken,
+    /// Installing Bun rather than Node.js
+    bun: bool,
+}
+
+/// Cheap to clone; clones share the same state and in-flight install
+#[derive(Clone)]
+pub struct RuntimeManager {
+    pub state: Arc<Mutex<RuntimeState>>,
+    flight: Arc<std::sync::Mutex<Option<InstallFlight>>>,
+}
+
+impl Default for RuntimeManager {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(RuntimeState::default())),
+            flight: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+}
+
+impl RuntimeManager {
+    /// Get the runtime directory
+    pub fn runtime_dir() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|d| d.join("simplestclaw").join("runtime"))
+    }
+
+    /// Get path to bundled node binary
+    pub fn node_path() -> Option<PathBuf> {
+        let runtime_dir = Self::runtime_dir()?;
+        let node = node_binary(&runtime_dir.join(node_folder_name()?));
+
+        if node.exists() {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    /// Get path to bundled npx binary
+    pub fn npx_path() -> Option<PathBuf> {
+        let runtime_dir = Self::runtime_dir()?;
+        let npx = npx_binary(&runtime_dir.join(node_folder_name()?));
+
+        if npx.exists() {
+            Some(npx)
+        } else {
+            None
+        }
+    }
+
+    /// System Node.js to use instead of the bundled runtime, per the
+    /// configured runtime preference
+    pub fn preferred_system_node(config: &Config) -> Option<SystemNode> {
+        system::preferred_system_node(config)
+    }
+
+    /// System Node.js from the last probe, whatever the runtime preference
+    pub fn system_node() -> Option<SystemNode> {
+        system::cached()
+    }
+
+    /// Whether the gateway has a runtime to run on: for Node.js the bundled
+    /// runtime or a system one the runtime preference allows, for Bun the
+    /// managed or a system install
+    pub fn is_available() -> bool {
+        let config = Config::load().unwrap_or_default();
+        match config.gateway_host {
+            GatewayHost::Node => {
+                Self::is_installed() || Self::preferred_system_node(&config).is_some()
+            }
+            GatewayHost::Bun => bun::path().is_some(),
+        }
+    }
+
+    /// The Bun the gateway would run on, with its version checked
+    pub fn find_bun() -> Option<BunRuntime> {
+        bun::find()
+    }
+
+    /// Whether the app bundle ships the runtime version the app should be at.
+    /// Always false without the `bundled-node` feature.
+    pub fn has_bundled_runtime(app: &AppHandle) -> bool {
+        #[cfg(feature = "bundled-node")]
+        return bundled::find(app).is_some();
+
+        #[cfg(not(feature = "bundled-node"))]
+        {
+            let _ = app;
+            false
+        }
+    }
+
+    /// Check if runtime is installed with the correct version
+    pub fn is_installed() -> bool {
+        Self::node_path().is_some() && Self::npx_path().is_some()
+    }
+
+    /// Check if the version the runtime should be at is installed
+    /// Returns false if version mismatch (needs upgrade)
+    pub fn is_correct_version() -> bool {
+        let runtime_dir = match Self::runtime_dir() {
+            Some(d) => d,
+            None => return false,
+        };
+
+        let expected_folder = match target_folder_name() {
+            Some(folder) => folder,
+            None => return false,
+        };
+
+        // Check if the expected version folder exists
+        let expected_path = runtime_dir.join(expected_folder);
+        expected_path.exists()
+    }
+
+    /// Remove old Node.js versions to free space and force upgrade
+    pub async fn cleanup_old_versions() -> Result<(), RuntimeError> {
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+
+        if !runtime_dir.exists() {
+            return Ok(());
+        }
+
+        let current_folder = node_folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+        let target_folder = target_folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+
+        // Read all entries in runtime dir
+        let mut entries = tokio::fs::read_dir(&runtime_dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            // Remove old node versions (starts with "node-v" but isn't current)
+            if name.starts_with("node-v") && name != current_folder && name != target_folder {
+                println!("[runtime] Removing old Node.js version: {}", name);
+                let path = entry.path();
+                if path.is_dir() {
+                    let _ = tokio::fs::remove_dir_all(&path).await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get runtime status
+    pub async fn status(&self) -> RuntimeStatus {
+        let system_node = Self::preferred_system_node(&Config::load().unwrap_or_default());
+        let state = self.state.lock().await;
+
+        RuntimeStatus {
+            installed: Self::is_installed(),
+            version: if Self::is_installed() {
+                Some(version::current_version())
+            } else {
+                None
+            },
+            node_path: Self::node_path().map(|p| p.to_string_lossy().to_string()),
+            npx_path: Self::npx_path().map(|p| p.to_string_lossy().to_string()),
+            downloading: state.downloading,
+            download_progress: state.progress,
+            error: state.error.clone(),
+            system_node,
+        }
+    }
+
+    /// Run `work` as the single in-flight install. It runs on its own task,
+    /// so it keeps going even if the caller stops waiting.
+    fn start_flight<F>(
+        &self,
+        app: &AppHandle,
+        slot: &mut Option<InstallFlight>,
+        bun: bool,
+        work: impl FnOnce(RuntimeManager, InstallJob) -> F,
+    ) -> Shared<BoxFuture<'static, InstallResult>>
+    where
+        F: std::future::Future<Output = Result<(), RuntimeError>> + Send + 'static,
+    {
+        let cancel = CancelToken::default();
+        let job = InstallJob {
+            cancel: cancel.clone(),
+            progress: ProgressReporter::new(app, self.state.clone()),
+        };
+        let manager = self.clone();
+        let work = work(self.clone(), job);
+        let task = tauri::async_runtime::spawn(async move {
+            let result = work.await;
+            // Finish the flight before reporting so a new install can start
+            manager.flight.lock().unwrap().take();
+            manager.finish(&result).await;
+            result.map_err(Arc::new)
+        });
+
+        let future = async move {
+            task.await
+                .unwrap_or_else(|e| Err(Arc::new(RuntimeError::Task(e.to_string()))))
+        }
+        .boxed()
+        .shared();
+        *slot = Some(InstallFlight {
+            future: future.clone(),
+            cancel,
+            bun,
+        });
+        future
+    }
+
+    /// Mark the state as busy at the start of an install
+    async fn begin(&self) {
+        let mut state = self.state.lock().await;
+        state.downloading = true;
+        state.progress = 0.0;
+        state.error = None;
+    }
+
+    /// Record how an install ended. A cancelled install isn't an error.
+    async fn finish(&self, result: &Result<(), RuntimeError>) {
+        let mut state = self.state.lock().await;
+        state.downloading = false;
+        match result {
+            Ok(()) => {}
+            Err(RuntimeError::Cancelled) => state.progress = 0.0,
+            Err(e) => state.error = Some(e.into()),
+        }
+    }
+
+    /// Download and install the Node.js runtime
+    /// Will also upgrade if an older version is detected.
+    /// Concurrent callers share one install and all get its result.
+    pub async fn install(&self, app: &AppHandle) -> InstallResult {
+        loop {
+            let (future, shared) = {
+                let mut slot = self.flight.lock().unwrap();
+                match slot.as_ref() {
+                    Some(flight) => (flight.future.clone(), !flight.bun),
+                    None => {
+                        // Whether the installed version is right is only known
+                        // after resolving the constraint, which run_install does
+                        let app_handle = app.clone();
+                        let future =
+                            self.start_flight(app, &mut slot, false, |manager, job| async move {
+                                manager.run_install(&app_handle, &job).await
+                            });
+                        (future, true)
+                    }
+                }
+            };
+            if shared {
+                return future.await;
+            }
+            // A Bun install holds the slot; wait for it, then install Node.js
+            let _ = future.await;
+        }
+    }
+
+    /// Download and install Bun for the gateway. Shares the single install
+    /// slot with the Node.js runtime: concurrent Bun installs share one
+    /// flight, and a Node.js install is waited for first.
+    pub async fn install_bun(&self, app: &AppHandle) -> InstallResult {
+        loop {
+            let (future, shared) = {
+                let mut slot = self.flight.lock().unwrap();
+                match slot.as_ref() {
+                    Some(flight) => (flight.future.clone(), flight.bun),
+                    None => {
+                        if bun::managed_path().is_some() {
+                            return Ok(());
+                        }
+                        let future =
+                            self.start_flight(app, &mut slot, true, |manager, job| async move {
+                                manager.run_install_bun(&job).await
+                            });
+                        (future, true)
+                    }
+                }
+            };
+            if shared {
+                return future.await;
+            }
+            let _ = future.await;
+        }
+    }
+
+    async fn run_install_bun(&self, job: &InstallJob) -> Result<(), RuntimeError> {
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+        tokio::fs::create_dir_all(&runtime_dir).await?;
+
+        self.begin().await;
+
+        let config = Config::load().unwrap_or_default();
+        if config.verify_node_signature {
+            println!(
+                "[runtime] Bun is verified by checksum only; signature checks apply to Node.js"
+            );
+        }
+        let client = network::http_client(&config)?;
+        bun::install(&client, &runtime_dir, job).await
+    }
+
+    /// Cancel the running install or import, if any, and wait for it to stop.
+    /// Returns whether there was one to cancel.
+    pub async fn cancel_install(&self) -> bool {
+        let flight = self
+            .flight
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|f| (f.future.clone(), f.cancel.clone()));
+        match flight {
+            Some((future, cancel)) => {
+                println!("[runtime] Cancelling runtime install");
+                cancel.cancel();
+                let _ = future.await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn run_install(&self, app: &AppHandle, job: &InstallJob) -> Result<(), RuntimeError> {
+        // A runtime shipped with the app is used as is, without the network
+        #[cfg(feature = "bundled-node")]
+        if let Some(bundled) = bundled::find(app) {
+            return self.install_bundled(bundled, job).await;
+        }
+        #[cfg(not(feature = "bundled-node"))]
+        let _ = app;
+
+        // The release index may name a newer version than the one installed
+        job.cancel.run(Self::refresh_version()).await?;
+        if Self::is_installed() && Self::is_correct_version() {
+            return Ok(());
+        }
+
+        let folder_name = target_folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+
+        // Create runtime directory
+        tokio::fs::create_dir_all(&runtime_dir).await?;
+
+        self.begin().await;
+
+        self.download_and_extract(&folder_name, &runtime_dir, job)
+            .await
+    }
+
+    async fn download_and_extract(
+        &self,
+        folder_name: &str,
+        runtime_dir: &Path,
+        job: &InstallJob,
+    ) -> Result<(), RuntimeError> {
+        let config = Config::load().unwrap_or_default();
+        let client = network::http_client(&config)?;
+        let url = node_archive_url(&network::node_mirror(&config))
+            .ok_or(RuntimeError::UnsupportedPlatform)?;
+        let url = url.as_str();
+
+        // Make sure the rest of the download and the unpacked runtime fit
+        // before starting, rather than failing halfway through
+        let temp_file = runtime_dir.join(DOWNLOAD_TEMP_FILE);
+        let partial = std::fs::metadata(&temp_file).map(|m| m.len()).unwrap_or(0);
+        let archive_size = job
+            .cancel
+            .run(async { Ok(preflight::archive_size(&client, url).await) })
+            .await?;
+        preflight::check_dir(
+            runtime_dir,
+            archive_size.saturating_sub(partial) + preflight::EXPANDED_BYTES,
+        )?;
+
+        // Look up the expected hash first so a bad archive is never extracted
+        let file_name = checksum::archive_file_name(url);
+        let expected_sha256 = job
+            .cancel
+            .run(checksum::fetch_expected_sha256(
+                &client,
+                url,
+                config.verify_node_signature,
+            ))
+            .await?;
+
+        println!("[runtime] Downloading Node.js from {}", url);
+
+        // Download to temp file, hashing as we go. A partial download.tmp
+        // left by an earlier attempt is resumed.
+        job.progress.phase(InstallPhase::Downloading);
+        let on_progress = |downloaded, total| job.progress.downloaded(downloaded, total);
+        let download = match config.parallel_download {
+            true => {
+                download::download_file_parallel(&client, url, &temp_file, on_progress, &job.cancel)
+                    .await
+            }
+            false => {
+                download::download_file(&client, url, &temp_file, on_progress, &job.cancel).await
+            }
+        };
+        let actual_sha256 = match download {
+            Ok(sha256) => sha256,
+            Err(RuntimeError::Cancelled) => {
+                // A cancelled download shouldn't be resumed on the next launch
+                download::discard(&temp_file);
+                return Err(RuntimeError::Cancelled);
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Hashed while downloading; this just compares
+        job.progress.phase(InstallPhase::Verifying);
+        if actual_sha256 != expected_sha256 {
+            download::discard(&temp_file);
+            eprintln!(
+                "[runtime] Checksum mismatch for {}, refusing to extract",
+                file_name
+            );
+            return Err(RuntimeError::ChecksumMismatch {
+                file: file_name,
+                expected: expected_sha256,
+                actual: actual_sha256,
+            });
+        }
+        println!("[runtime] Download complete, checksum verified, extracting...");
+
+        let result = self
+            .install_archive(&temp_file, folder_name, runtime_dir, job)
+            .await;
+
+        // Clean up temp file
+        let _ = tokio::fs::remove_file(&temp_file).await;
+
+        result
+    }
+
+    /// Install the runtime shipped in the app bundle
+    #[cfg(feature = "bundled-node")]
+    async fn install_bundled(
+        &self,
+        bundled: bundled::Bundled,
+        job: &InstallJob,
+    ) -> Result<(), RuntimeError> {
+        // The bundle holds the compiled-in version, whatever was resolved
+        let folder_name = folder_name_for(NODE_VERSION).ok_or(RuntimeError::UnsupportedPlatform)?;
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+        if node_binary(&runtime_dir.join(&folder_name)).is_file() {
+            return Ok(());
+        }
+        tokio::fs::create_dir_all(&runtime_dir).await?;
+
+        self.begin().await;
+
+        match bundled {
+            bundled::Bundled::Archive(archive) => {
+                let file_name =
+                    archive_name_for(NODE_VERSION).ok_or(RuntimeError::UnsupportedPlatform)?;
+                println!("[runtime] Installing Node.js from the app bundle");
+                // The archive is covered by the app's own code signature, so
+                // the SHASUMS256.txt next to it is trusted as is: checking its
+                // gpg signature would fetch the release keys over the network,
+                // which bundled installs exist to avoid.
+                self.import_verified(&archive, &file_name, &folder_name, &runtime_dir, false, job)
+                    .await
+            }
+            #[cfg(unix)]
+            bundled::Bundled::Folder(folder) => {
+                println!(
+                    "[runtime] Linking Node.js from the app bundle: {}",
+                    folder.display()
+                );
+                job.progress.phase(InstallPhase::Finalizing);
+                // Link under a staging name first, like an extracted archive
+                let staged = runtime_dir.join(format!("{}{}", STAGING_PREFIX, folder_name));
+                if staged.symlink_metadata().is_ok() {
+                    tokio::fs::remove_dir_all(&staged).await?;
+                }
+                bundled::link_folder(&folder, &staged).await?;
+                let result = Self::promote(&staged, &runtime_dir.join(&folder_name)).await;
+                let _ = tokio::fs::remove_file(&staged).await;
+                result?;
+                if let Err(e) = Self::cleanup_old_versions().await {
+                    println!("[runtime] Warning: Failed to cleanup old versions: {}", e);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Extract a verified archive into a staging directory, check the result,
+    /// then move it into place with a single rename. A crash or full disk
+    /// mid-extraction leaves only a staging directory behind, never a
+    /// half-populated version folder that would pass `is_correct_version`.
+    async fn install_archive(
+        &self,
+        archive: &Path,
+        folder_name: &str,
+        runtime_dir: &Path,
+        job: &InstallJob,
+    ) -> Result<(), RuntimeError> {
+        let staging_dir = runtime_dir.join(format!("{}{}", STAGING_PREFIX, folder_name));
+        if staging_dir.exists() {
+            tokio::fs::remove_dir_all(&staging_dir).await?;
+        }
+        tokio::fs::create_dir_all(&staging_dir).await?;
+
+        let result = self
+            .stage_archive(archive, folder_name, &staging_dir, job)
+            .await;
+        // Last chance to cancel; once promoted the install is complete
+        let result = match result.and_then(|staged| job.cancel.check().map(|_| staged)) {
+            Ok(staged) => Self::promote(&staged, &runtime_dir.join(folder_name)).await,
+            Err(e) => Err(e),
+        };
+
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+        result?;
+
+        println!(
+            "[runtime] Node.js {} installed successfully",
+            folder_version(folder_name).unwrap_or_default()
+        );
+
+        // Only now that the new version is in place
+        if let Err(e) = Self::cleanup_old_versions().await {
+            println!("[runtime] Warning: Failed to cleanup old versions: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Extract into `staging_dir` and verify; returns the staged version folder
+    async fn stage_archive(
+        &self,
+        archive: &Path,
+        folder_name: &str,
+        staging_dir: &Path,
+        job: &InstallJob,
+    ) -> Result<PathBuf, RuntimeError> {
+        job.progress.phase(InstallPhase::Extracting);
+        let (archive, dest, root, extract_job) = (
+            archive.to_path_buf(),
+            staging_dir.to_path_buf(),
+            folder_name.to_string(),
+            job.clone(),
+        );
+        tokio::task::spawn_blocking(move || extract::extract(&archive, &dest, &root, &extract_job))
+            .await
+            .map_err(|e| RuntimeError::Extract(format!("Task error: {}", e)))??;
+
+        job.progress.phase(InstallPhase::Finalizing);
+
+        let staged = staging_dir.join(folder_name);
+        let node = node_binary(&staged);
+        if !node.exists() || !npx_binary(&staged).exists() {
+            return Err(RuntimeError::Verification(format!(
+                "{} is missing node or npx",
+                folder_name
+            )));
+        }
+
+        // Make binaries executable on Unix
+        #[cfg(not(target_os = "windows"))]
+        {
+            let bin_dir = staged.join("bin");
+            for entry in std::fs::read_dir(&bin_dir)? {
+                if let Ok(entry) = entry {
+                    let _ = std::fs::set_permissions(
+                        entry.path(),
+                        std::os::unix::fs::PermissionsExt::from_mode(0o755),
+                    );
+                }
+            }
+        }
+
+        // The binary must actually run and be the version we asked for
+        let version = doctor::node_version(&node).await?;
+        let expected = format!("v{}", folder_version(folder_name).unwrap_or_default());
+        if version != expected {
+            return Err(RuntimeError::Verification(format!(
+                "node --version reported {:?}, expected {}",
+                version, expected
+            )));
+        }
+
+        Ok(staged)
+    }
+
+    /// Move a verified version folder into its final place
+    async fn promote(staged: &Path, target: &Path) -> Result<(), RuntimeError> {
+        // Replace an incomplete folder left by an older, non-atomic install
+        if target.exists() {
+            println!("[runtime] Replacing existing {}", target.display());
+            tokio::fs::remove_dir_all(target).await?;
+        }
+        tokio::fs::rename(staged, target).await?;
+        Ok(())
+    }
+
+    /// Resolve the configured version constraint against the release index
+    /// (cached for a day). Returns the version the runtime should be at.
+    pub async fn refresh_version() -> Result<String, RuntimeError> {
+        let config = Config::load().unwrap_or_default();
+        let (_, _, index_key) = node_platform().ok_or(RuntimeError::UnsupportedPlatform)?;
+        let client = network::http_client(&config)?;
+        version::refresh(
+            &client,
+            &network::node_mirror(&config),
+            version::configured_constraint(&config),
+            index_key,
+        )
+        .await
+    }
+
+    /// Self-test the installed runtime
+    pub async fn check() -> Result<RuntimeReport, RuntimeError> {
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+        let folder_name = node_folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+        Ok(doctor::run_checks(&runtime_dir.join(folder_name)).await)
+    }
+
+    /// Reinstall the runtime if any check fails, then check again
+    pub async fn repair(&self, app: &AppHandle) -> Result<RuntimeReport, Arc<RuntimeError>> {
+        let report = Self::check().await?;
+        if report.healthy {
+            return Ok(report);
+        }
+
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| c.status == doctor::CheckStatus::Fail)
+            .map(|c| c.id.as_str())
+            .collect();
+        println!(
+            "[runtime] Runtime checks failed ({}), reinstalling",
+            failed.join(", ")
+        );
+
+        // Remove the broken folder so install() doesn't consider it current,
+        // unless an install is already replacing it
+        if self.flight.lock().unwrap().is_none() {
+            if let (Some(dir), Some(name)) = (Self::runtime_dir(), node_folder_name()) {
+                let folder = dir.join(name);
+                if folder.exists() {
+                    tokio::fs::remove_dir_all(&folder)
+                        .await
+                        .map_err(|e| Arc::new(RuntimeError::Io(e)))?;
+                }
+            }
+        }
+
+        self.install(app).await?;
+        Ok(Self::check().await?)
+    }
+
+    /// Remove what an interrupted install leaves behind: staging directories
+    /// and a finished download that was never extracted. A partial download
+    /// that can still be resumed is kept.
+    pub fn cleanup_interrupted_install() {
+        let runtime_dir = match Self::runtime_dir() {
+            Some(d) => d,
+            None => return,
+        };
+        let entries = match std::fs::read_dir(&runtime_dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(STAGING_PREFIX) && entry.path().is_dir() {
+                println!("[runtime] Removing leftover staging directory: {}", name);
+                let _ = std::fs::remove_dir_all(entry.path());
+            }
+        }
+
+        let temp_file = runtime_dir.join(DOWNLOAD_TEMP_FILE);
+        if temp_file.exists() && !download::is_resumable(&temp_file) {
+            println!("[runtime] Removing leftover {}", DOWNLOAD_TEMP_FILE);
+            download::discard(&temp_file);
+        }
+        bun::cleanup_interrupted_download(&runtime_dir);
+    }
+
+    /// Install from an archive the user downloaded themselves (for machines
+    /// without internet access). The archive must be the one this app expects
+    /// and is checked against a SHASUMS256.txt saved next to it, or fetched
+    /// from the configured mirror when there is none.
+    pub async fn import_archive(&self, app: &AppHandle, path: &Path) -> InstallResult {
+        let future = {
+            let mut slot = self.flight.lock().unwrap();
+            if slot.is_some() {
+                return Err(Arc::new(RuntimeError::Busy));
+            }
+            let path = path.to_path_buf();
+            self.start_flight(app, &mut slot, false, |manager, job| async move {
+                manager.run_import(&path, &job).await
+            })
+        };
+        future.await
+    }
+
+    async fn run_import(&self, path: &Path, job: &InstallJob) -> Result<(), RuntimeError> {
+        let expected_name = node_archive_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+        let folder_name = target_folder_name().ok_or(RuntimeError::UnsupportedPlatform)?;
+        let actual_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !is_node_archive_name(&actual_name) {
+            return Err(RuntimeError::WrongArchive {
+                expected: expected_name,
+                actual: actual_name,
+            });
+        }
+
+        let runtime_dir = Self::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+        tokio::fs::create_dir_all(&runtime_dir).await?;
+
+        self.begin().await;
+
+        let verify_signature = Config::load().unwrap_or_default().verify_node_signature;
+        self.import_verified(
+            path,
+            &actual_name,
+            &folder_name,
+            &runtime_dir,
+            verify_signature,
+            job,
+        )
+        .await
+    }
+
+    async fn import_verified(
+        &self,
+        path: &Path,
+        file_name: &str,
+        folder_name: &str,
+        runtime_dir: &Path,
+        verify_signature: bool,
+        job: &InstallJob,
+    ) -> Result<(), RuntimeError> {
+        let config = Config::load().unwrap_or_default();
+        preflight::check_dir(runtime_dir, preflight::EXPANDED_BYTES)?;
+
+        let expected_sha256 = match checksum::local_expected_sha256(path, verify_signature).await {
+            Some(result) => result?,
+            None => {
+                let client = network::http_client(&config)?;
+                let url = node_release_url(&network::node_mirror(&config), file_name);
+                job.cancel
+                    .run(checksum::fetch_expected_sha256(
+                        &client,
+                        &url,
+                        verify_signature,
+                    ))
+                    .await?
+            }
+        };
+
+        job.progress.phase(InstallPhase::Verifying);
+        let archive = path.to_path_buf();
+        let actual_sha256 = tokio::task::spawn_blocking(move || checksum::sha256_file(&archive))
+            .await
+            .map_err(|e| RuntimeError::Extract(format!("Task error: {}", e)))??;
+        if actual_sha256 != expected_sha256 {
+            return Err(RuntimeError::ChecksumMismatch {
+                file: file_name.to_string(),
+                expected: expected_sha256,
+                actual: actual_sha256,
+            });
+        }
+        job.cancel.check()?;
+        println!("[runtime] Importing {}, checksum verified", file_name);
+
+        self.install_archive(path, folder_name, runtime_dir, job)
+            .await
+    }
+}
+
+/// Check a Node.js version constraint before it is saved to Config
+pub fn validate_node_constraint(constraint: &str) -> Result<(), RuntimeError> {
+    version::parse_constraint(constraint).map(|_| ())
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn get_runtime_status(
+    manager: tauri::State<'_, RuntimeManager>,
+) -> Result<RuntimeStatus, AppError> {
+    Ok(manager.status().await)
+}
+
+#[tauri::command]
+pub async fn install_runtime(
+    app: AppHandle,
+    manager: tauri::State<'_, RuntimeManager>,
+) -> Result<(), AppError> {
+    manager.install(&app).await.map_err(AppError::from)
+}
+
+/// Install Node.js from a locally downloaded release archive
+#[tauri::command]
+pub async fn import_runtime_archive(
+    app: AppHandle,
+    manager: tauri::State<'_, RuntimeManager>,
+    path: String,
+) -> Result<(), AppError> {
+    manager
+        .import_archive(&app, Path::new(&path))
+        .await
+        .map_err(AppError::from)
+}
+
+/// Stop a running install or import and remove its partial files
+#[tauri::command]
+pub async fn cancel_runtime_install(
+    manager: tauri::State<'_, RuntimeManager>,
+) -> Result<bool, AppError> {
+    Ok(manager.cancel_install().await)
+}
+
+/// Look for a system Node.js regardless of the runtime preference. Probes
+/// again rather than using the cached result.
+#[tauri::command]
+pub fn detect_system_node() -> Option<SystemNode> {
+    system::probe()
+}
+
+/// Download and install Bun as the gateway host
+#[tauri::command]
+pub async fn install_bun(
+    app: AppHandle,
+    manager: tauri::State<'_, RuntimeManager>,
+) -> Result<(), AppError> {
+    manager.install_bun(&app).await.map_err(AppError::from)
+}
+
+/// The Bun the gateway would run on, if any
+#[tauri::command]
+pub fn detect_bun() -> Option<BunRuntime> {
+    RuntimeManager::find_bun()
+}
+
+/// Run the runtime self-test
+#[tauri::command]
+pub async fn check_runtime() -> Result<RuntimeReport, AppError> {
+    RuntimeManager::check().await.map_err(AppError::from)
+}
+
+/// Reinstall the runtime if the self-test fails; returns the report afterwards
+#[tauri::command]
+pub async fn repair_runtime(
+    app: AppHandle,
+    manager: tauri::State<'_, RuntimeManager>,
+) -> Result<RuntimeReport, AppError> {
+    manager.repair(&app).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn is_runtime_installed() -> bool {
+    RuntimeManager::is_installed() && RuntimeManager::is_correct_version()
+}
+
+#[tauri::command]
+pub fn needs_runtime_upgrade() -> bool {
+    // Has some Node installed but not the correct version
+    RuntimeManager::is_installed() && !RuntimeManager::is_correct_version()
+}
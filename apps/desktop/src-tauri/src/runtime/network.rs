@@ -0,0 +1,63 @@
+//! Network settings for runtime downloads
+//!
+//! Corporate networks often block nodejs.org and only allow an internal mirror
+//! behind an HTTP proxy that re-signs TLS with its own CA. The mirror, proxy
+//! and extra CA certificates come from Config.
+
+use std::time::Duration;
+
+use super::RuntimeError;
+use crate::config::Config;
+
+/// Official Node.js download location, used unless Config names a mirror
+pub const DEFAULT_NODE_MIRROR: &str = "https://nodejs.org/dist";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Base URL that release directories (`v<version>/`) live under
+pub fn node_mirror(config: &Config) -> String {
+    config
+        .node_mirror_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_NODE_MIRROR)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// HTTP client honoring the configured proxy and extra CA certificates.
+/// Without a configured proxy, reqwest still picks up HTTP(S)_PROXY from the environment.
+pub fn http_client(config: &Config) -> Result<reqwest::Client, RuntimeError> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(concat!("simplestclaw/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(CONNECT_TIMEOUT);
+
+    if let Some(proxy) = config
+        .proxy_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| RuntimeError::Network(format!("Invalid proxy URL: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &config.extra_ca_certs {
+        let pem = std::fs::read(path).map_err(|e| {
+            RuntimeError::Network(format!("Failed to read CA certificate {}: {}", path, e))
+        })?;
+        // A file may hold a whole bundle
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+            RuntimeError::Network(format!("Invalid CA certificate {}: {}", path, e))
+        })?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| RuntimeError::Network(format!("Failed to create HTTP client: {}", e)))
+}
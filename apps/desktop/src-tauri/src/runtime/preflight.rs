@@ -0,0 +1,188 @@
+//! Pre-flight checks before writing a runtime or package to disk
+//!
+//! A full disk otherwise shows up as an opaque write error halfway through
+//! an extraction. Before starting, make sure the target directory can be
+//! written, that its filesystem isn't read-only or mounted noexec (Node.js
+//! couldn't run from it), and that there is room for the download plus its
+//! unpacked contents.
+
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Used when the server doesn't report the archive size
+const ESTIMATED_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// A Node.js release unpacks to roughly 200MB; leave some headroom
+pub const EXPANDED_BYTES: u64 = 256 * 1024 * 1024;
+
+const SIZE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MB: u64 = 1024 * 1024;
+
+/// Remediation hints are added where these become an AppError
+#[derive(Error, Debug, Clone)]
+pub enum PreflightError {
+    #[error("Not enough disk space in {path}: {needed_mb} MB needed, {available_mb} MB free.")]
+    InsufficientSpace {
+        path: String,
+        needed_mb: u64,
+        available_mb: u64,
+    },
+    #[error("Cannot write to {path}: {reason}.")]
+    NotWritable { path: String, reason: String },
+    #[error("{0} is on a read-only filesystem.")]
+    ReadOnly(String),
+    #[error("{0} is on a filesystem mounted with noexec, so Node.js can't run from it.")]
+    NoExec(String),
+}
+
+/// Free space and mount flags of the filesystem holding a path
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+struct MountInfo {
+    available: u64,
+    read_only: bool,
+    noexec: bool,
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn c_path(path: &Path) -> std::io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(std::ffi::CString::new(path.as_os_str().as_bytes())?)
+}
+
+#[cfg(target_os = "linux")]
+#[allow(clippy::unnecessary_cast)] // The statvfs fields are 32-bit on some targets
+fn mount_info(path: &Path) -> std::io::Result<MountInfo> {
+    let path = c_path(path)?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::zeroed();
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out pointer
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: statvfs succeeded, so it filled in `stat`
+    let stat = unsafe { stat.assume_init() };
+    Ok(MountInfo {
+        available: stat.f_bavail as u64 * stat.f_frsize as u64,
+        read_only: stat.f_flag & libc::ST_RDONLY != 0,
+        noexec: stat.f_flag & libc::ST_NOEXEC != 0,
+    })
+}
+
+/// macOS's statvfs has no noexec flag, so use statfs
+#[cfg(target_os = "macos")]
+fn mount_info(path: &Path) -> std::io::Result<MountInfo> {
+    let path = c_path(path)?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::zeroed();
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out pointer
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: statfs succeeded, so it filled in `stat`
+    let stat = unsafe { stat.assume_init() };
+    Ok(MountInfo {
+        available: stat.f_bavail * stat.f_bsize as u64,
+        read_only: stat.f_flags & libc::MNT_RDONLY as u32 != 0,
+        noexec: stat.f_flags & libc::MNT_NOEXEC as u32 != 0,
+    })
+}
+
+/// Windows has no noexec; a read-only volume fails the write probe instead
+#[cfg(target_os = "windows")]
+fn mount_info(path: &Path) -> std::io::Result<MountInfo> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory_name: *const u16,
+            free_bytes_available_to_caller: *mut u64,
+            total_number_of_bytes: *mut u64,
+            total_number_of_free_bytes: *mut u64,
+        ) -> i32;
+    }
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated, `available` is a valid out pointer and
+    // the other two may be null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(MountInfo {
+        available,
+        read_only: false,
+        noexec: false,
+    })
+}
+
+/// Create and remove a file to prove the directory is writable
+fn probe_write(dir: &Path) -> Result<(), PreflightError> {
+    let probe = dir.join(format!(".preflight-{}", std::process::id()));
+    let result = std::fs::write(&probe, b"");
+    let _ = std::fs::remove_file(&probe);
+    result.map_err(|e| PreflightError::NotWritable {
+        path: dir.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Check that `dir` (created if missing) is writable, executable and has
+/// `needed` bytes free
+pub fn check_dir(dir: &Path, needed: u64) -> Result<(), PreflightError> {
+    let display = dir.display().to_string();
+    std::fs::create_dir_all(dir).map_err(|e| PreflightError::NotWritable {
+        path: display.clone(),
+        reason: e.to_string(),
+    })?;
+
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    match mount_info(dir) {
+        Ok(info) => {
+            if info.read_only {
+                return Err(PreflightError::ReadOnly(display));
+            }
+            if info.noexec {
+                return Err(PreflightError::NoExec(display));
+            }
+            if info.available < needed {
+                return Err(PreflightError::InsufficientSpace {
+                    path: display,
+                    needed_mb: needed.div_ceil(MB),
+                    available_mb: info.available / MB,
+                });
+            }
+        }
+        Err(e) => eprintln!("[runtime] Could not check free space in {}: {}", display, e),
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let _ = needed;
+
+    probe_write(dir)
+}
+
+/// Size of the archive at `url` from a HEAD request, or an estimate if the
+/// server doesn't say
+pub async fn archive_size(client: &reqwest::Client, url: &str) -> u64 {
+    let response = client.head(url).timeout(SIZE_TIMEOUT).send().await;
+    response
+        .ok()
+        .filter(|r| r.status().is_success())
+        .and_then(|r| {
+            r.headers()
+                .get(reqwest::header::CONTENT_LENGTH)?
+                .to_str()
+                .ok()?
+                .parse()
+                .ok()
+        })
+        .unwrap_or(ESTIMATED_ARCHIVE_BYTES)
+}
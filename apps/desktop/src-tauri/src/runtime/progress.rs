@@ -0,0 +1,168 @@
+//! Install progress pushed to the frontend
+//!
+//! Emits throttled `runtime-progress` events so the UI doesn't have to poll
+//! `get_runtime_status`. Throughput is smoothed so the ETA doesn't jump
+//! around with every chunk.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use super::{DownloadProgress, InstallPhase, RuntimeState};
+
+pub const RUNTIME_PROGRESS_EVENT: &str = "runtime-progress";
+
+/// At most one event per interval, except on phase changes and completion
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Weight of the newest throughput sample in the moving average
+const SPEED_SMOOTHING: f64 = 0.3;
+
+struct Inner {
+    progress: DownloadProgress,
+    last_emit: Option<Instant>,
+    /// (time, bytes) at the previous throughput sample
+    last_sample: Option<(Instant, u64)>,
+    speed: Option<f64>,
+}
+
+/// Shared by the async download and the blocking extraction; clones report
+/// into the same progress
+#[derive(Clone)]
+pub struct ProgressReporter {
+    app: AppHandle,
+    state: Arc<tokio::sync::Mutex<RuntimeState>>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl ProgressReporter {
+    pub fn new(app: &AppHandle, state: Arc<tokio::sync::Mutex<RuntimeState>>) -> Self {
+        Self {
+            app: app.clone(),
+            state,
+            inner: Arc::new(Mutex::new(Inner {
+                progress: DownloadProgress {
+                    phase: InstallPhase::Downloading,
+                    bytes_downloaded: 0,
+                    total_bytes: None,
+                    bytes_per_sec: None,
+                    eta_secs: None,
+                    entries_extracted: 0,
+                    total_entries: None,
+                    percent: 0.0,
+                },
+                last_emit: None,
+                last_sample: None,
+                speed: None,
+            })),
+        }
+    }
+
+    /// Start a new phase; always emitted
+    pub fn phase(&self, phase: InstallPhase) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.progress.phase = phase;
+        inner.progress.percent = match phase {
+            InstallPhase::Downloading | InstallPhase::Extracting => 0.0,
+            InstallPhase::Verifying | InstallPhase::Finalizing => 100.0,
+        };
+        inner.progress.bytes_per_sec = None;
+        inner.progress.eta_secs = None;
+        if phase != InstallPhase::Downloading {
+            // The download is done; the status percentage stays at 100
+            if let Ok(mut state) = self.state.try_lock() {
+                state.progress = 100.0;
+            }
+        }
+        self.emit(&mut inner, true);
+    }
+
+    /// Bytes received so far and the total size, if known
+    pub fn downloaded(&self, downloaded: u64, total: Option<u64>) {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+
+        // Resumed downloads start above zero; only count bytes from this run
+        match inner.last_sample {
+            Some((at, bytes)) if downloaded >= bytes => {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                if elapsed >= EMIT_INTERVAL.as_secs_f64() {
+                    let sample = (downloaded - bytes) as f64 / elapsed;
+                    inner.speed = Some(match inner.speed {
+                        Some(speed) => speed + SPEED_SMOOTHING * (sample - speed),
+                        None => sample,
+                    });
+                    inner.last_sample = Some((now, downloaded));
+                }
+            }
+            // First report, or the download restarted from scratch
+            _ => inner.last_sample = Some((now, downloaded)),
+        }
+
+        let percent = total
+            .filter(|t| *t > 0)
+            .map(|t| downloaded as f32 / t as f32 * 100.0)
+            .unwrap_or(0.0);
+        let speed = inner.speed;
+        let progress = &mut inner.progress;
+        progress.bytes_downloaded = downloaded;
+        progress.total_bytes = total;
+        progress.percent = percent;
+        progress.bytes_per_sec = speed.map(|s| s as u64);
+        progress.eta_secs = match (total, speed) {
+            (Some(total), Some(speed)) if speed > 0.0 => {
+                Some((total.saturating_sub(downloaded) as f64 / speed).ceil() as u64)
+            }
+            _ => None,
+        };
+
+        // Update the polled status too (skipped if busy; the next chunk catches up)
+        if total.is_some() {
+            if let Ok(mut state) = self.state.try_lock() {
+                state.progress = percent;
+            }
+        }
+
+        let done = total == Some(downloaded);
+        self.emit(&mut inner, done);
+    }
+
+    /// Extraction is about to unpack `total` entries
+    pub fn entries_total(&self, total: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.progress.total_entries = Some(total);
+        inner.progress.entries_extracted = 0;
+        self.emit(&mut inner, true);
+    }
+
+    /// One more archive entry unpacked
+    pub fn entry_extracted(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let progress = &mut inner.progress;
+        progress.entries_extracted += 1;
+        if let Some(total) = progress.total_entries.filter(|t| *t > 0) {
+            progress.percent =
+                (progress.entries_extracted as f32 / total as f32 * 100.0).min(100.0);
+        }
+        let done = progress.total_entries == Some(progress.entries_extracted);
+        self.emit(&mut inner, done);
+    }
+
+    fn emit(&self, inner: &mut Inner, force: bool) {
+        let now = Instant::now();
+        let due = inner
+            .last_emit
+            .map(|at| now.duration_since(at) >= EMIT_INTERVAL)
+            .unwrap_or(true);
+        if !force && !due {
+            return;
+        }
+        inner.last_emit = Some(now);
+        if let Err(e) = self
+            .app
+            .emit(RUNTIME_PROGRESS_EVENT, inner.progress.clone())
+        {
+            eprintln!("[runtime] Failed to emit progress: {}", e);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! System Node.js detection
+//!
+//! Developers usually have Node.js already (nvm, volta, fnm, asdf, mise,
+//! Homebrew). With the runtime preference set to "system" or "auto" the app
+//! uses that instead of downloading its own, as long as it's new enough.
+//!
+//! `node` on PATH is often a version manager shim (a bash script for asdf and
+//! mise, a native binary for volta, `npx.cmd` next to it on Windows), so node
+//! is asked for its real binary and the gateway runs npm's npx-cli.js from
+//! next to that, like the bundled runtime does.
+//!
+//! Probing spawns processes, so the result is cached: status polls read the
+//! cache, which is filled at startup and refreshed by `detect_system_node`.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::config::{Config, RuntimePreference};
+use crate::sidecar::find_system_command;
+
+/// Oldest system Node.js that "auto" accepts. Node 25+ is required (see NODE_VERSION).
+pub const MIN_SYSTEM_NODE: Version = Version::new(25, 0, 0);
+
+/// A Node.js installation found on the system
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemNode {
+    /// The real node binary, not the shim found on PATH
+    pub node_path: String,
+    /// npm's npx-cli.js, run with `node_path`
+    pub npx_path: String,
+    /// e.g. "25.2.1"; None if `node --version` failed
+    pub version: Option<String>,
+    /// Whether the version meets MIN_SYSTEM_NODE
+    pub compatible: bool,
+}
+
+/// Result of the last probe; None until the first one
+static LAST_PROBE: Mutex<Option<Option<SystemNode>>> = Mutex::new(None);
+
+/// Ask `node` for its version and the path of the binary behind any shim
+fn query_node(node: &str) -> Option<(Option<Version>, PathBuf)> {
+    let output = Command::new(node)
+        .args(["-p", "process.version + '\\n' + process.execPath"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let version = lines
+        .next()
+        .and_then(|v| Version::parse(v.trim().trim_start_matches('v')).ok());
+    let exec_path = lines.next().map(str::trim).filter(|p| !p.is_empty())?;
+    Some((version, PathBuf::from(exec_path)))
+}
+
+/// npm's npx-cli.js for a node binary: `<prefix>/lib/node_modules` on Unix,
+/// `node_modules` next to node.exe on Windows
+fn npx_cli_for(node: &Path) -> Option<PathBuf> {
+    let bin_dir = node.parent()?;
+    #[cfg(target_os = "windows")]
+    let npx_cli = bin_dir.join("node_modules/npm/bin/npx-cli.js");
+    #[cfg(not(target_os = "windows"))]
+    let npx_cli = bin_dir
+        .parent()?
+        .join("lib/node_modules/npm/bin/npx-cli.js");
+    npx_cli.exists().then_some(npx_cli)
+}
+
+fn detect() -> Option<SystemNode> {
+    let shim = find_system_command("node")?;
+    let (version, node) = query_node(&shim)?;
+    let Some(npx_cli) = npx_cli_for(&node) else {
+        println!(
+            "[runtime] System Node.js at {:?} has no npm next to it, ignoring it",
+            node
+        );
+        return None;
+    };
+    Some(SystemNode {
+        node_path: node.to_string_lossy().to_string(),
+        npx_path: npx_cli.to_string_lossy().to_string(),
+        compatible: version.as_ref().is_some_and(|v| *v >= MIN_SYSTEM_NODE),
+        version: version.map(|v| v.to_string()),
+    })
+}
+
+/// Look for node on the system, check its version and find its npx-cli.js.
+/// A node without npx-cli.js is unusable. Blocking; updates the cache.
+pub fn probe() -> Option<SystemNode> {
+    let node = detect();
+    *LAST_PROBE.lock().unwrap() = Some(node.clone());
+    node
+}
+
+/// The last probe's result, probing now if there hasn't been one
+pub fn cached() -> Option<SystemNode> {
+    let last = LAST_PROBE.lock().unwrap().clone();
+    match last {
+        Some(node) => node,
+        None => probe(),
+    }
+}
+
+/// The system Node.js the configured preference wants used instead of the
+/// bundled runtime, if any
+pub fn preferred_system_node(config: &Config) -> Option<SystemNode> {
+    match config.runtime_preference {
+        RuntimePreference::Bundled => None,
+        RuntimePreference::System => cached(),
+        RuntimePreference::Auto => cached().filter(|node| node.compatible),
+    }
+}
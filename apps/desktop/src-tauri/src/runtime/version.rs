@@ -0,0 +1,230 @@
+//! Node.js version resolution
+//!
+//! Rather than pinning one Node.js build per app release, the runtime follows
+//! a semver constraint (Config.node_version_constraint, or the default below)
+//! and picks the newest release in the mirror's `index.json` that satisfies
+//! it and ships a build for this platform. The result is cached in the
+//! runtime directory, so path lookups stay offline and synchronous. Without
+//! network access the last resolved version is used, or failing that the
+//! compiled-in NODE_VERSION.
+//!
+//! The resolved version is only what the next install aims for. The runtime
+//! keeps running on the installed version (the newest installed folder that
+//! satisfies the constraint) until that install has been promoted, so a
+//! failed or cancelled upgrade never leaves it without a runtime.
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{folder_version, network, node_binary, RuntimeError, RuntimeManager, NODE_VERSION};
+use crate::config::Config;
+
+/// Used when Config has no constraint. Node 25+ is required (see NODE_VERSION).
+pub const DEFAULT_CONSTRAINT: &str = ">=25.6.0, <26";
+
+const CACHE_FILE: &str = "node-version.json";
+
+/// Re-check the index at most this often
+const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const INDEX_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A resolved version only applies to the mirror and constraint it was
+/// resolved for
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionCache {
+    mirror: String,
+    constraint: String,
+    /// Without the leading "v"
+    version: String,
+    resolved_at: u64,
+}
+
+/// One release in index.json; `files` names the builds it ships,
+/// e.g. "linux-x64", "osx-arm64-tar", "win-x64-zip"
+#[derive(Debug, Deserialize)]
+struct IndexEntry {
+    version: String,
+    files: Vec<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parse a constraint. Accepts semver syntax (">=25.6.0, <26"), npm-style
+/// space-separated ranges (">=25.6 <26") and bare versions, which pin
+/// exactly ("25.6.0" or "v25.6.0").
+pub fn parse_constraint(constraint: &str) -> Result<VersionReq, RuntimeError> {
+    let trimmed = constraint.trim();
+    let invalid =
+        |e: semver::Error| RuntimeError::VersionConstraint(format!("\"{}\": {}", trimmed, e));
+
+    if let Ok(version) = Version::parse(trimmed.trim_start_matches('v')) {
+        return VersionReq::parse(&format!("={}", version)).map_err(invalid);
+    }
+
+    // npm separates comparators with spaces, semver with commas.
+    // A lone operator (">= 25") belongs to the part after it.
+    let mut comparators = Vec::new();
+    let mut operator = String::new();
+    for part in trimmed.split([' ', ',']).filter(|p| !p.is_empty()) {
+        if part.chars().all(|c| "<>=~^".contains(c)) {
+            operator.push_str(part);
+        } else {
+            comparators.push(format!("{}{}", std::mem::take(&mut operator), part));
+        }
+    }
+    let normalized = comparators.join(", ");
+    VersionReq::parse(&normalized).map_err(invalid)
+}
+
+/// Newest release matching `req` with a build for `platform_key`
+fn resolve(index: &[IndexEntry], req: &VersionReq, platform_key: &str) -> Option<Version> {
+    index
+        .iter()
+        .filter(|entry| entry.files.iter().any(|f| f == platform_key))
+        .filter_map(|entry| Version::parse(entry.version.trim_start_matches('v')).ok())
+        .filter(|version| version.pre.is_empty() && req.matches(version))
+        .max()
+}
+
+/// The constraint the runtime follows
+pub fn configured_constraint(config: &Config) -> &str {
+    config
+        .node_version_constraint
+        .as_deref()
+        .unwrap_or(DEFAULT_CONSTRAINT)
+}
+
+/// The cached resolution, if it was made for `mirror` and `constraint`
+fn load_cache(mirror: &str, constraint: &str) -> Option<VersionCache> {
+    let path = RuntimeManager::runtime_dir()?.join(CACHE_FILE);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<VersionCache>(&contents)
+        .ok()
+        .filter(|c| c.mirror == mirror && c.constraint == constraint)
+}
+
+fn save_cache(cache: &VersionCache) -> Result<(), RuntimeError> {
+    let dir = RuntimeManager::runtime_dir().ok_or(RuntimeError::NoRuntimeDir)?;
+    std::fs::create_dir_all(&dir)?;
+    let json =
+        serde_json::to_string_pretty(cache).map_err(|e| RuntimeError::Download(e.to_string()))?;
+    std::fs::write(dir.join(CACHE_FILE), json)?;
+    Ok(())
+}
+
+/// The version the runtime should be at: the last one resolved for the
+/// configured mirror and constraint, or the compiled-in default if there is
+/// none (e.g. right after the constraint changed)
+pub fn target_version() -> String {
+    let config = Config::load().unwrap_or_default();
+    load_cache(
+        &network::node_mirror(&config),
+        configured_constraint(&config),
+    )
+    .map(|c| c.version)
+    .unwrap_or_else(|| NODE_VERSION.to_string())
+}
+
+/// Newest installed version that satisfies the configured constraint.
+/// Version folders only appear once an install is promoted, so a resolved
+/// version becomes the installed one when its install completes.
+pub fn installed_version() -> Option<String> {
+    let config = Config::load().unwrap_or_default();
+    let req = parse_constraint(configured_constraint(&config)).ok();
+
+    std::fs::read_dir(RuntimeManager::runtime_dir()?)
+        .ok()?
+        .flatten()
+        .filter(|entry| node_binary(&entry.path()).is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            Version::parse(folder_version(&name)?).ok()
+        })
+        .filter(|version| req.as_ref().is_none_or(|req| req.matches(version)))
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// The version the runtime is at: the installed one, or if there is none
+/// the one it should be at
+pub fn current_version() -> String {
+    installed_version().unwrap_or_else(target_version)
+}
+
+async fn fetch_index(
+    client: &reqwest::Client,
+    mirror: &str,
+) -> Result<Vec<IndexEntry>, RuntimeError> {
+    let url = format!("{}/index.json", mirror);
+    let response = client
+        .get(&url)
+        .timeout(INDEX_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| RuntimeError::Download(format!("{}: {}", url, e)))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| RuntimeError::Download(format!("{}: {}", url, e)))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| RuntimeError::Download(format!("Invalid {}: {}", url, e)))
+}
+
+/// Resolve `constraint` against the mirror's index and cache the result.
+/// Network problems fall back to the cached or compiled-in version; only
+/// an invalid constraint or no matching release is an error.
+pub async fn refresh(
+    client: &reqwest::Client,
+    mirror: &str,
+    constraint: &str,
+    platform_key: &str,
+) -> Result<String, RuntimeError> {
+    let req = parse_constraint(constraint)?;
+
+    let cached = load_cache(mirror, constraint);
+    if let Some(ref cache) = cached {
+        if now_secs().saturating_sub(cache.resolved_at) < CACHE_TTL.as_secs() {
+            return Ok(cache.version.clone());
+        }
+    }
+
+    let index = match fetch_index(client, mirror).await {
+        Ok(index) => index,
+        Err(e) => {
+            let fallback = cached.map(|c| c.version).unwrap_or_else(target_version);
+            eprintln!(
+                "[runtime] Could not fetch Node.js release index ({}), using {}",
+                e, fallback
+            );
+            return Ok(fallback);
+        }
+    };
+
+    let version = resolve(&index, &req, platform_key)
+        .ok_or_else(|| RuntimeError::NoMatchingVersion(constraint.to_string()))?
+        .to_string();
+    println!(
+        "[runtime] Resolved Node.js {} for constraint \"{}\"",
+        version, constraint
+    );
+
+    let cache = VersionCache {
+        mirror: mirror.to_string(),
+        constraint: constraint.to_string(),
+        version: version.clone(),
+        resolved_at: now_secs(),
+    };
+    if let Err(e) = save_cache(&cache) {
+        eprintln!("[runtime] Failed to cache resolved version: {}", e);
+    }
+    Ok(version)
+}
@@ -0,0 +1,596 @@
+//! Full-Text Search Across Transcripts
+//!
+//! Keeps a local SQLite FTS5 index of every message in the OpenClaw transcripts
+//! (see `transcripts.rs`). The index is incremental: for each transcript we
+//! remember the byte offset of the last complete line, so appended JSONL lines
+//! are picked up without re-reading the file. A file is only resumed if it's
+//! the same file (inode) and the bytes before that offset are unchanged;
+//! files rewritten in place (compaction, a restore) are re-indexed from
+//! scratch and deleted ones dropped. Everything runs offline and transcripts
+//! are streamed line by line, so memory use doesn't grow with the number of
+//! sessions.
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::error::{AppError, ErrorCode};
+use crate::transcripts::{self, MessageRole, ParseState, ParsedLine, TranscriptFile};
+
+/// Bump when the schema changes; the index is rebuilt from scratch
+const SCHEMA_VERSION: i64 = 2;
+
+/// How often the background job picks up new transcript lines
+pub const REFRESH_INTERVAL_SECS: u64 = 15;
+
+/// Bytes before the indexed offset that must be unchanged to resume a file
+const TAIL_BYTES: u64 = 4096;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Marks the matched terms in `SearchHit::snippet` (STX/ETX control characters,
+/// so the frontend can highlight without worrying about HTML in messages)
+const HIGHLIGHT_START: &str = "\u{2}";
+const HIGHLIGHT_END: &str = "\u{3}";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    /// Free text; every word must match (prefix matching on each word)
+    pub text: Option<String>,
+    /// Unix millis, inclusive
+    pub from: Option<i64>,
+    /// Unix millis, inclusive
+    pub to: Option<i64>,
+    pub model: Option<String>,
+    /// Only messages that call (or return results from) this tool
+    pub tool: Option<String>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub session_id: String,
+    pub message_id: Option<String>,
+    /// 1-based line in the session transcript
+    pub line: usize,
+    pub timestamp: Option<i64>,
+    pub role: String,
+    pub model: Option<String>,
+    pub tools: Vec<String>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStats {
+    pub sessions: usize,
+    pub messages: usize,
+    /// Messages added by the last refresh
+    pub added: usize,
+}
+
+/// Location of the SQLite index (under the app data dir)
+pub fn index_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("simplestclaw").join("search").join("index.sqlite3"))
+}
+
+fn db_error(context: &str, e: rusqlite::Error) -> AppError {
+    AppError::new(ErrorCode::SearchIndexFailed, format!("{}: {}", context, e))
+}
+
+/// Identifies the file behind a path, so a transcript replaced by a new file
+/// isn't resumed. Not available on Windows, where only the tail check applies.
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<i64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino() as i64)
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<i64> {
+    None
+}
+
+/// Hash of the bytes just before `offset`, checked on the next refresh to
+/// make sure the indexed part of the file hasn't been rewritten
+fn tail_hash(file: &mut fs::File, offset: u64) -> std::io::Result<Option<String>> {
+    if offset == 0 {
+        return Ok(None);
+    }
+    let start = offset.saturating_sub(TAIL_BYTES);
+    let mut buf = vec![0u8; (offset - start) as usize];
+    file.seek(SeekFrom::Start(start))?;
+    file.read_exact(&mut buf)?;
+    Ok(Some(hex::encode(Sha256::digest(&buf))))
+}
+
+/// What the index remembers about a transcript file
+struct FileState {
+    offset: u64,
+    line: usize,
+    model: Option<String>,
+    modified: i64,
+    inode: Option<i64>,
+    tail: Option<String>,
+}
+
+fn role_name(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::ToolResult => "toolResult",
+        MessageRole::System => "system",
+        MessageRole::Other => "other",
+    }
+}
+
+/// Turn free text into an FTS5 query: each word quoted (so punctuation and
+/// FTS operators in user input are literal) and prefix-matched
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+impl SearchIndex {
+    pub fn open() -> Result<Self, AppError> {
+        let path = index_path()
+            .ok_or_else(|| AppError::new(ErrorCode::ConfigUnavailable, "Could not determine data directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io("Failed to create search dir", e))?;
+        }
+        let conn = Connection::open(&path).map_err(|e| db_error("Failed to open search index", e))?;
+        let index = Self { conn };
+        index.migrate()?;
+        Ok(index)
+    }
+
+    fn migrate(&self) -> Result<(), AppError> {
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|e| db_error("Search index error", e))?;
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        println!("[search] Building search index (schema v{})", SCHEMA_VERSION);
+        self.conn
+            .execute_batch(&format!(
+                "PRAGMA journal_mode = WAL;
+                 DROP TABLE IF EXISTS files;
+                 DROP TABLE IF EXISTS messages;
+                 DROP TABLE IF EXISTS message_tools;
+                 DROP TABLE IF EXISTS messages_fts;
+                 CREATE TABLE files (
+                     path TEXT PRIMARY KEY,
+                     session_id TEXT NOT NULL,
+                     offset INTEGER NOT NULL,
+                     line INTEGER NOT NULL,
+                     model TEXT,
+                     modified INTEGER NOT NULL,
+                     inode INTEGER,
+                     tail TEXT
+                 );
+                 CREATE TABLE messages (
+                     id INTEGER PRIMARY KEY,
+                     path TEXT NOT NULL,
+                     session_id TEXT NOT NULL,
+                     message_id TEXT,
+                     line INTEGER NOT NULL,
+                     timestamp INTEGER,
+                     role TEXT NOT NULL,
+                     model TEXT
+                 );
+                 CREATE INDEX messages_path ON messages(path);
+                 CREATE INDEX messages_timestamp ON messages(timestamp);
+                 CREATE INDEX messages_model ON messages(model);
+                 CREATE TABLE message_tools (
+                     message INTEGER NOT NULL,
+                     name TEXT NOT NULL
+                 );
+                 CREATE INDEX message_tools_message ON message_tools(message);
+                 CREATE INDEX message_tools_name ON message_tools(name);
+                 CREATE VIRTUAL TABLE messages_fts USING fts5(text, tokenize = 'unicode61 remove_diacritics 2');
+                 PRAGMA user_version = {};",
+                SCHEMA_VERSION
+            ))
+            .map_err(|e| db_error("Failed to create search index", e))
+    }
+
+    /// Remove everything indexed for one transcript file
+    fn forget(&self, path: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "DELETE FROM messages_fts WHERE rowid IN (SELECT id FROM messages WHERE path = ?1)",
+            params![path],
+        )?;
+        self.conn.execute(
+            "DELETE FROM message_tools WHERE message IN (SELECT id FROM messages WHERE path = ?1)",
+            params![path],
+        )?;
+        self.conn.execute("DELETE FROM messages WHERE path = ?1", params![path])?;
+        self.conn.execute("DELETE FROM files WHERE path = ?1", params![path])?;
+        Ok(())
+    }
+
+    /// Index lines appended to one transcript since the last refresh.
+    /// Returns the number of messages added.
+    fn index_file(&mut self, file: &TranscriptFile) -> Result<usize, AppError> {
+        let path = file.path.to_string_lossy().to_string();
+        let stored: Option<FileState> = self
+            .conn
+            .query_row(
+                "SELECT offset, line, model, modified, inode, tail FROM files WHERE path = ?1",
+                params![path],
+                |row| {
+                    Ok(FileState {
+                        offset: row.get::<_, i64>(0)? as u64,
+                        line: row.get::<_, i64>(1)? as usize,
+                        model: row.get(2)?,
+                        modified: row.get(3)?,
+                        inode: row.get(4)?,
+                        tail: row.get(5)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| db_error("Search index error", e))?;
+
+        let mut handle = fs::File::open(&file.path)?;
+        let inode = file_id(&handle.metadata()?);
+        let (mut offset, mut line_no, model) = match stored {
+            // Unchanged since the last refresh
+            Some(state)
+                if state.inode == inode
+                    && state.offset == file.size_bytes
+                    && state.modified == file.modified =>
+            {
+                return Ok(0)
+            }
+            // Only appended to since the last refresh
+            Some(state)
+                if state.inode == inode
+                    && state.offset < file.size_bytes
+                    && tail_hash(&mut handle, state.offset)? == state.tail =>
+            {
+                (state.offset, state.line, state.model)
+            }
+            // New file, or it was replaced or rewritten: index from scratch
+            _ => (0, 0, None),
+        };
+
+        let tx = self.conn.transaction().map_err(|e| db_error("Search index error", e))?;
+        if offset == 0 {
+            tx.execute(
+                "DELETE FROM messages_fts WHERE rowid IN (SELECT id FROM messages WHERE path = ?1)",
+                params![path],
+            )
+            .and_then(|_| {
+                tx.execute(
+                    "DELETE FROM message_tools WHERE message IN (SELECT id FROM messages WHERE path = ?1)",
+                    params![path],
+                )
+            })
+            .and_then(|_| tx.execute("DELETE FROM messages WHERE path = ?1", params![path]))
+            .map_err(|e| db_error("Search index error", e))?;
+        }
+
+        handle.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(handle);
+
+        let mut state = ParseState { model, ..Default::default() };
+        let mut added = 0;
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let read = reader.read_until(b'\n', &mut buf)?;
+            // Stop at EOF or at a line that is still being written
+            if read == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+            offset += read as u64;
+            line_no += 1;
+
+            let line = String::from_utf8_lossy(&buf);
+            let ParsedLine::Message(message) = transcripts::parse_line(&line, line_no, &mut state) else {
+                continue;
+            };
+
+            let mut tools: HashSet<&str> = message.tool_calls.iter().map(|c| c.name.as_str()).collect();
+            tools.extend(message.tool_results.iter().filter_map(|r| r.tool_name.as_deref()));
+
+            let mut text = message.text.clone();
+            for result in &message.tool_results {
+                text.push('\n');
+                text.push_str(&result.content);
+            }
+
+            tx.execute(
+                "INSERT INTO messages (path, session_id, message_id, line, timestamp, role, model)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    path,
+                    file.session_id,
+                    message.id,
+                    message.line as i64,
+                    message.timestamp,
+                    role_name(&message.role),
+                    message.model,
+                ],
+            )
+            .map_err(|e| db_error("Search index error", e))?;
+            let rowid = tx.last_insert_rowid();
+            tx.execute(
+                "INSERT INTO messages_fts (rowid, text) VALUES (?1, ?2)",
+                params![rowid, text],
+            )
+            .map_err(|e| db_error("Search index error", e))?;
+            for tool in tools.iter().filter(|t| !t.is_empty()) {
+                tx.execute(
+                    "INSERT INTO message_tools (message, name) VALUES (?1, ?2)",
+                    params![rowid, tool],
+                )
+                .map_err(|e| db_error("Search index error", e))?;
+            }
+            added += 1;
+        }
+
+        let tail = tail_hash(reader.get_mut(), offset)?;
+        tx.execute(
+            "INSERT INTO files (path, session_id, offset, line, model, modified, inode, tail)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(path) DO UPDATE SET
+                 offset = ?3, line = ?4, model = ?5, modified = ?6, inode = ?7, tail = ?8",
+            params![
+                path,
+                file.session_id,
+                offset as i64,
+                line_no as i64,
+                state.model,
+                file.modified,
+                inode,
+                tail,
+            ],
+        )
+        .map_err(|e| db_error("Search index error", e))?;
+        tx.commit().map_err(|e| db_error("Search index error", e))?;
+        Ok(added)
+    }
+
+    /// Bring the index up to date with the transcripts on disk
+    pub fn refresh(&mut self) -> Result<IndexStats, AppError> {
+        let files = transcripts::transcript_files();
+        let on_disk: HashSet<String> = files.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+
+        // Drop transcripts that were deleted
+        let indexed: Vec<String> = {
+            let mut stmt = self.conn.prepare("SELECT path FROM files").map_err(|e| db_error("Search index error", e))?;
+            let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| db_error("Search index error", e))?;
+            rows.filter_map(Result::ok).collect()
+        };
+        for path in indexed.iter().filter(|p| !on_disk.contains(*p)) {
+            self.forget(path).map_err(|e| db_error("Search index error", e))?;
+        }
+
+        let mut added = 0;
+        for file in &files {
+            match self.index_file(file) {
+                Ok(n) => added += n,
+                Err(e) => eprintln!("[search] Failed to index {:?}: {}", file.path, e),
+            }
+        }
+        if added > 0 {
+            println!("[search] Indexed {} new message(s)", added);
+        }
+
+        let messages: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM messages", [], |row| row.get(0))
+            .map_err(|e| db_error("Search index error", e))?;
+        Ok(IndexStats { sessions: files.len(), messages: messages as usize, added })
+    }
+
+    pub fn search(&self, query: &SearchQuery) -> Result<SearchResults, AppError> {
+        let offset = query.offset.unwrap_or(0);
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        let text_query = query.text.as_deref().and_then(fts_query);
+
+        if let Some(ref fts) = text_query {
+            values.push(fts.clone().into());
+            conditions.push(format!("messages_fts MATCH ?{}", values.len()));
+        }
+        if let Some(from) = query.from {
+            values.push(from.into());
+            conditions.push(format!("m.timestamp >= ?{}", values.len()));
+        }
+        if let Some(to) = query.to {
+            values.push(to.into());
+            conditions.push(format!("m.timestamp <= ?{}", values.len()));
+        }
+        if let Some(ref model) = query.model.as_ref().filter(|m| !m.is_empty()) {
+            values.push(model.to_string().into());
+            conditions.push(format!("m.model = ?{}", values.len()));
+        }
+        if let Some(ref tool) = query.tool.as_ref().filter(|t| !t.is_empty()) {
+            values.push(tool.to_string().into());
+            conditions.push(format!(
+                "m.id IN (SELECT message FROM message_tools WHERE name = ?{})",
+                values.len()
+            ));
+        }
+
+        let snippet = if text_query.is_some() {
+            format!(
+                "snippet(messages_fts, 0, '{}', '{}', '…', 16)",
+                HIGHLIGHT_START, HIGHLIGHT_END
+            )
+        } else {
+            "substr(messages_fts.text, 1, 160)".to_string()
+        };
+        let order = if text_query.is_some() { "rank" } else { "m.timestamp DESC" };
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        values.push((limit as i64).into());
+        let limit_param = values.len();
+        values.push((offset as i64).into());
+        let offset_param = values.len();
+
+        let sql = format!(
+            "SELECT m.id, m.session_id, m.message_id, m.line, m.timestamp, m.role, m.model, {snippet}
+             FROM messages_fts JOIN messages m ON m.id = messages_fts.rowid
+             {where_clause}
+             ORDER BY {order}
+             LIMIT ?{limit_param} OFFSET ?{offset_param}",
+        );
+
+        let mut stmt = self.conn.prepare(&sql).map_err(|e| db_error("Search index error", e))?;
+        let rows = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    SearchHit {
+                        session_id: row.get(1)?,
+                        message_id: row.get(2)?,
+                        line: row.get::<_, i64>(3)? as usize,
+                        timestamp: row.get(4)?,
+                        role: row.get(5)?,
+                        model: row.get(6)?,
+                        tools: Vec::new(),
+                        snippet: row.get(7)?,
+                    },
+                ))
+            })
+            .map_err(|e| db_error("Search failed", e))?;
+
+        let mut tools_stmt = self
+            .conn
+            .prepare("SELECT name FROM message_tools WHERE message = ?1")
+            .map_err(|e| db_error("Search index error", e))?;
+        let mut hits = Vec::new();
+        for row in rows {
+            let (id, mut hit) = row.map_err(|e| db_error("Search failed", e))?;
+            hit.tools = tools_stmt
+                .query_map(params![id], |r| r.get(0))
+                .map_err(|e| db_error("Search index error", e))?
+                .filter_map(Result::ok)
+                .collect();
+            hits.push(hit);
+        }
+
+        Ok(SearchResults { hits, offset, limit })
+    }
+}
+
+/// Shared, lazily opened search index
+#[derive(Default)]
+pub struct SearchManager {
+    index: Arc<Mutex<Option<SearchIndex>>>,
+}
+
+impl SearchManager {
+    /// Run `f` against the index on a blocking thread, opening it on first use
+    pub async fn with_index<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SearchIndex) -> Result<T, AppError> + Send + 'static,
+    {
+        let index = self.index.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut guard = index
+                .lock()
+                .map_err(|_| AppError::internal("Search index lock poisoned"))?;
+            if guard.is_none() {
+                *guard = Some(SearchIndex::open()?);
+            }
+            let index = guard.as_mut().ok_or_else(|| AppError::internal("Search index unavailable"))?;
+            f(index)
+        })
+        .await?
+    }
+
+    /// Pick up appended transcript lines (called periodically in the background)
+    pub async fn refresh(&self) -> Result<IndexStats, AppError> {
+        self.with_index(|index| index.refresh()).await
+    }
+
+    /// Close the index so its files can be deleted; it is reopened (and
+    /// rebuilt if missing) on next use
+    pub fn close(&self) {
+        if let Ok(mut guard) = self.index.lock() {
+            *guard = None;
+        }
+    }
+
+    /// Close and delete the index after transcripts were replaced wholesale
+    /// (e.g. by a restore), since unchanged sizes would hide the new content.
+    /// The next use rebuilds it from scratch.
+    pub fn rebuild(&self) {
+        if let Ok(mut guard) = self.index.lock() {
+            *guard = None;
+            if let Some(dir) = index_path().and_then(|p| p.parent().map(PathBuf::from)) {
+                if let Err(e) = fs::remove_dir_all(&dir) {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        eprintln!("[search] Failed to delete index: {}", e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn search_transcripts(
+    manager: tauri::State<'_, SearchManager>,
+    query: SearchQuery,
+) -> Result<SearchResults, AppError> {
+    manager
+        .with_index(move |index| {
+            // Cheap when nothing changed: unchanged files are skipped by size
+            index.refresh()?;
+            index.search(&query)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn refresh_search_index(
+    manager: tauri::State<'_, SearchManager>,
+) -> Result<IndexStats, AppError> {
+    manager.refresh().await
+}
@@ -10,14 +10,20 @@
 //! - OpenClaw gateway: https://docs.clawd.bot/cli/gateway
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Manager;
 
-use crate::config::Config;
+use crate::activity::{log_activity, ActivityManager};
+use crate::config::{Config, GatewayHost};
+use crate::error::{AppError, ErrorCode};
 use crate::runtime::RuntimeManager;
 
+/// Room npx needs to install the openclaw package and its dependencies
+const OPENCLAW_PACKAGE_BYTES: u64 = 512 * 1024 * 1024;
+
 /// Gateway connection info returned to the frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,12 +38,15 @@ pub struct GatewayInfo {
 pub struct GatewayStatus {
     pub running: bool,
     pub info: Option<GatewayInfo>,
-    pub error: Option<String>,
+    pub error: Option<AppError>,
+    /// Managed bootstrap files (e.g. AGENTS.md) kept because the user edited them
+    pub bootstrap_conflicts: Vec<String>,
 }
 
 pub struct SidecarState {
     pub child: Option<Child>,
     pub info: Option<GatewayInfo>,
+    pub bootstrap_conflicts: Vec<String>,
 }
 
 impl Default for SidecarState {
@@ -45,6 +54,7 @@ impl Default for SidecarState {
         Self {
             child: None,
             info: None,
+            bootstrap_conflicts: Vec::new(),
         }
     }
 }
@@ -66,8 +76,8 @@ impl SidecarManager {
     ///
     /// Uses the bundled Node.js runtime so users don't need to install
     /// anything. On first launch, the runtime is automatically downloaded.
-    pub fn start(&self, _app: &AppHandle) -> Result<GatewayInfo, String> {
-        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+    pub fn start(&self, app: &AppHandle) -> Result<GatewayInfo, AppError> {
+        let mut state = self.state.lock().map_err(|_| AppError::internal("Gateway state lock poisoned"))?;
 
         // Check if already running and healthy
         if let Some(ref mut child) = state.child {
@@ -93,7 +103,7 @@ impl SidecarManager {
         }
 
         // Check if port is already in use (another instance might be running)
-        let config = Config::load().map_err(|e| format!("Failed to load config: {}", e))?;
+        let config = Config::load()?;
         let port = config.gateway_port;
         
         if std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok() {
@@ -102,58 +112,71 @@ impl SidecarManager {
             drop(state); // Release lock before cleanup
             kill_orphaned_gateway_processes();
             std::thread::sleep(std::time::Duration::from_millis(1500));
-            state = self.state.lock().map_err(|e| e.to_string())?;
+            state = self.state.lock().map_err(|_| AppError::internal("Gateway state lock poisoned"))?;
             
             // Check again
             if std::net::TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok() {
-                return Err(format!(
-                    "Port {} is still in use. Another gateway may be running. \
-                     Please close all simplestclaw windows and try again.",
-                    port
-                ));
+                return Err(AppError::new(
+                    ErrorCode::PortInUse,
+                    format!("Port {} is still in use. Another gateway may be running.", port),
+                )
+                .with_hint("Please close all simplestclaw windows and try again.")
+                .with_details(serde_json::json!({ "port": port })));
             }
         }
 
-        // Check if runtime is installed
-        if !RuntimeManager::is_installed() {
-            return Err(
-                "Node.js runtime not installed. Please wait for the download to complete, \
-                 or click 'Install Runtime' in Settings.".to_string()
-            );
-        }
-
         use crate::config::{ApiMode, Provider};
 
+        // Check if runtime is installed (or a system Node.js may be used)
+        if !RuntimeManager::is_available() {
+            return Err(runtime_not_installed(config.gateway_host));
+        }
+
         // Validate credentials based on API mode
         match config.api_mode {
             ApiMode::Managed => {
                 if config.license_key.is_none() {
-                    return Err("No license key configured. Please sign up or log in.".to_string());
+                    return Err(AppError::new(
+                        ErrorCode::MissingLicenseKey,
+                        "No license key configured.",
+                    )
+                    .with_hint("Please sign up or log in."));
                 }
             }
             ApiMode::Byo => {
-                if config.anthropic_api_key.is_none() {
-                    return Err("No API key configured. Please enter your API key in Settings.".to_string());
+                if config.api_key(&config.provider).is_none() {
+                    return Err(AppError::new(
+                        ErrorCode::MissingApiKey,
+                        format!("No API key configured for {}.", config.provider.display_name()),
+                    )
+                    .with_hint("Please enter your API key in Settings.")
+                    .with_details(serde_json::json!({ "provider": config.provider })));
                 }
             }
         }
 
         let token = generate_token();
 
-        // Get bundled node path (prioritize bundled over system)
-        let (node_cmd, npx_cli_path) = find_node_and_npx().ok_or(
-            "Node.js runtime not found. Please click 'Install Runtime' in Settings."
-        )?;
+        // Node.js (bundled or system, per the runtime preference) or Bun
+        let host = HostCommand::resolve(&config)?;
 
-        println!("[openclaw] Starting gateway via bundled Node.js...");
-        println!("[openclaw] Using node at: {}", node_cmd);
-        println!("[openclaw] Using npx-cli at: {}", npx_cli_path);
+        println!("[openclaw] Starting gateway via {}...", host.name);
+        println!("[openclaw] Using {} at: {}", host.name, host.program);
+        println!("[openclaw] Launcher args: {:?}", host.args);
         println!("[openclaw] API mode: {:?}", config.api_mode);
 
         // Clear npx cache to prevent corrupted package issues
         // The npx cache at ~/.npm/_npx can become corrupted and cause
         // "Cannot find package" errors with dependencies like axios
-        clear_npx_cache();
+        if config.gateway_host == GatewayHost::Node {
+            clear_npx_cache();
+        }
+
+        // The host installs the openclaw package into its cache; fail early
+        // with a clear message rather than halfway through the install
+        if let Some(ref cache) = host.package_cache {
+            crate::runtime::check_dir(cache, OPENCLAW_PACKAGE_BYTES)?;
+        }
 
         // Clear stale device pairing data from previous gateway runs.
         // OpenClaw 2026.2.14+ enforces explicit scopes on device tokens.
@@ -164,10 +187,10 @@ impl SidecarManager {
         clear_device_pairing_data();
 
         // Get the bin directory for PATH
-        let node_path = std::path::Path::new(&node_cmd);
-        let bin_dir = node_path.parent().map(|p| p.to_string_lossy().to_string());
+        let program_path = std::path::Path::new(&host.program);
+        let bin_dir = program_path.parent().map(|p| p.to_string_lossy().to_string());
         
-        // Build PATH with node bin directory first
+        // Build PATH with the runtime's bin directory first
         let path_env = if let Some(ref bin) = bin_dir {
             let existing_path = std::env::var("PATH").unwrap_or_default();
             format!("{}:{}", bin, existing_path)
@@ -177,12 +200,11 @@ impl SidecarManager {
 
         println!("[openclaw] PATH: {}", path_env.chars().take(200).collect::<String>());
 
-        // Spawn node directly with npx-cli.js to avoid shebang issues
+        // Spawn node directly with npx-cli.js (or bun) to avoid shebang issues
         // This ensures we use our bundled node, not whatever is in /usr/bin/env
-        let mut cmd = Command::new(&node_cmd);
-        cmd.args([
-                &npx_cli_path,
-                "--yes",  // Auto-confirm package installation
+        let mut cmd = Command::new(&host.program);
+        cmd.args(&host.args)
+            .args([
                 "openclaw",
                 "gateway",
                 "--port",
@@ -194,6 +216,31 @@ impl SidecarManager {
             .env("PATH", &path_env)
             .env("OPENCLAW_GATEWAY_TOKEN", &token);
         
+        // Model, tool profile and directory come from the active workspace
+        let effective_model = config.effective_model();
+        let tool_profile = config.effective_tool_profile();
+        let workspace = ActiveWorkspace::resolve(&config)?;
+        println!("[openclaw] Workspace: {} ({:?})", config.active_workspace, workspace.dir);
+
+        // Ensure workspace directory exists and seed bootstrap files
+        std::fs::create_dir_all(&workspace.dir)
+            .map_err(|e| AppError::io("Failed to create workspace dir", e))?;
+        let bootstrap_conflicts = seed_workspace_bootstrap(&workspace.dir, &tool_profile)?;
+        if !bootstrap_conflicts.is_empty() {
+            if let Some(activity) = app.try_state::<ActivityManager>() {
+                for name in &bootstrap_conflicts {
+                    let path = workspace.dir.join(name);
+                    log_activity(
+                        &activity,
+                        "file_write",
+                        &format!("Kept your edits to {}; the new default was saved as {}.new", name, name),
+                        "blocked",
+                        Some(&path.to_string_lossy()),
+                    );
+                }
+            }
+        }
+
         // Configure provider credentials based on API mode
         match config.api_mode {
             ApiMode::Managed => {
@@ -202,8 +249,8 @@ impl SidecarManager {
                 // We write to the default location (not via OPENCLAW_CONFIG_PATH) so
                 // OpenClaw's full runtime (workspace, tools, scopes) initialises correctly.
                 let license_key = config.license_key.as_deref().unwrap_or("");
-                let model = config.selected_model.as_deref().unwrap_or("claude-sonnet-4-5-20250929");
-                let _ = write_managed_openclaw_config(license_key, model, &config.tool_profile, config.allow_exec)?;
+                let model = effective_model.as_deref().unwrap_or("claude-sonnet-4-5-20250929");
+                let _ = write_managed_openclaw_config(license_key, model, &tool_profile, config.allow_exec, &workspace)?;
                 // In managed mode, requests route through the SimplestClaw proxy which
                 // swaps in the real provider API key. We set a placeholder here because
                 // OpenClaw requires the env var to be present at startup.
@@ -213,9 +260,10 @@ impl SidecarManager {
                 // Write ~/.openclaw/openclaw.json with gateway.mode=local, tools.profile=full,
                 // and the default model. This gives the operator full tool access and fixes
                 // "missing scope: operator.write" errors.
-                let api_key = config.anthropic_api_key.as_deref().unwrap_or("");
-                let selected = config.selected_model.as_deref();
-                write_byo_openclaw_config(api_key, &config.provider, selected, &config.tool_profile, config.allow_exec)?;
+                // Each provider has its own key; never hand one provider's key to another
+                let api_key = config.api_key(&config.provider).unwrap_or("");
+                let selected = effective_model.as_deref();
+                write_byo_openclaw_config(api_key, &config.provider, selected, &tool_profile, config.allow_exec, &workspace)?;
                 // No OPENCLAW_CONFIG_PATH -- we write to the default ~/.openclaw/openclaw.json
                 // so OpenClaw's full runtime (workspace, credentials, scopes) initialises.
 
@@ -241,8 +289,12 @@ impl SidecarManager {
             cmd.process_group(0);
         }
         
-        let mut child = cmd.spawn()
-            .map_err(|e| format!("Failed to start gateway: {}", e))?;
+        let mut child = cmd.spawn().map_err(|e| {
+            AppError::new(
+                ErrorCode::GatewayStartFailed,
+                format!("Failed to start gateway: {}", e),
+            )
+        })?;
 
         let info = GatewayInfo {
             url: format!("ws://localhost:{}", port),
@@ -270,25 +322,38 @@ impl SidecarManager {
                     println!("[openclaw] Process exited with code: {}", exit_code);
                     println!("[openclaw] stderr: {}", stderr_output);
                     
+                    let details = serde_json::json!({
+                        "exitCode": exit_code,
+                        "program": host.program,
+                        "stderr": stderr_output,
+                    });
+
                     // Exit code 127 = command not found
                     if exit_code == 127 {
-                        return Err(format!(
-                            "Gateway failed: command not found (exit code 127). \
-                             Node path: {}. This usually means the Node.js binary couldn't execute. \
-                             stderr: {}",
-                            node_cmd, stderr_output
-                        ));
+                        return Err(AppError::new(
+                            ErrorCode::GatewayExited,
+                            format!(
+                                "Gateway failed: command not found (exit code 127). \
+                                 {} path: {}. This usually means the {} binary couldn't execute.",
+                                host.name, host.program, host.name
+                            ),
+                        )
+                        .with_hint("Reinstall the runtime from Settings.")
+                        .with_details(details));
                     }
                     
-                    return Err(format!(
-                        "Gateway process exited unexpectedly with status: {}. \
-                         stderr: {}",
-                        status, stderr_output
-                    ));
+                    return Err(AppError::new(
+                        ErrorCode::GatewayExited,
+                        format!("Gateway process exited unexpectedly with status: {}.", status),
+                    )
+                    .with_details(details));
                 }
                 Ok(None) => {} // Still running, good
                 Err(e) => {
-                    return Err(format!("Failed to check gateway status: {}", e));
+                    return Err(AppError::new(
+                        ErrorCode::GatewayStartFailed,
+                        format!("Failed to check gateway status: {}", e),
+                    ));
                 }
             }
 
@@ -306,22 +371,24 @@ impl SidecarManager {
         if !ready {
             // Kill the process if it never became ready
             let _ = child.kill();
-            return Err(
-                "Gateway failed to start within 30 seconds. \
-                 Please check your internet connection and try again.".to_string()
-            );
+            return Err(AppError::new(
+                ErrorCode::GatewayTimeout,
+                "Gateway failed to start within 30 seconds.",
+            )
+            .with_hint("Please check your internet connection and try again."));
         }
 
         state.child = Some(child);
         state.info = Some(info.clone());
+        state.bootstrap_conflicts = bootstrap_conflicts;
 
         println!("[openclaw] Gateway running at {}", info.url);
         Ok(info)
     }
 
     /// Stop the gateway
-    pub fn stop(&self) -> Result<(), String> {
-        let mut state = self.state.lock().map_err(|e| e.to_string())?;
+    pub fn stop(&self) -> Result<(), AppError> {
+        let mut state = self.state.lock().map_err(|_| AppError::internal("Gateway state lock poisoned"))?;
 
         if let Some(ref mut child) = state.child {
             println!("[openclaw] Stopping gateway...");
@@ -347,16 +414,19 @@ impl SidecarManager {
             Err(_) => return GatewayStatus { 
                 running: false, 
                 info: None,
-                error: Some("Lock error".to_string()),
+                error: Some(AppError::internal("Gateway state lock poisoned")),
+                bootstrap_conflicts: Vec::new(),
             },
         };
 
-        // Check if runtime is installed
-        if !RuntimeManager::is_installed() {
+        // Check if runtime is installed (or a system Node.js may be used)
+        if !RuntimeManager::is_available() {
+            let host = Config::load().unwrap_or_default().gateway_host;
             return GatewayStatus {
                 running: false,
                 info: None,
-                error: Some("runtime_not_installed".to_string()),
+                error: Some(runtime_not_installed(host)),
+                bootstrap_conflicts: Vec::new(),
             };
         }
 
@@ -378,19 +448,109 @@ impl SidecarManager {
             running: state.child.is_some(),
             info: state.info.clone(),
             error: None,
+            bootstrap_conflicts: state.bootstrap_conflicts.clone(),
+        }
+    }
+}
+
+/// How to launch `openclaw gateway` on the configured host
+struct HostCommand {
+    /// "Node.js" or "Bun", for messages
+    name: &'static str,
+    /// Executable to spawn
+    program: String,
+    /// Arguments that come before "openclaw gateway ..."
+    args: Vec<String>,
+    /// Where the host caches the openclaw package it installs
+    package_cache: Option<std::path::PathBuf>,
+}
+
+impl HostCommand {
+    fn resolve(config: &Config) -> Result<Self, AppError> {
+        let home = dirs::home_dir();
+        match config.gateway_host {
+            GatewayHost::Node => {
+                let (node_cmd, npx_cli_path) = find_node_and_npx(config)
+                    .ok_or_else(|| runtime_not_installed(GatewayHost::Node))?;
+                Ok(Self {
+                    name: "Node.js",
+                    program: node_cmd,
+                    // Auto-confirm package installation
+                    args: vec![npx_cli_path, "--yes".to_string()],
+                    package_cache: home.map(|h| h.join(".npm")),
+                })
+            }
+            GatewayHost::Bun => {
+                // Check the version before use; an old Bun fails in confusing ways
+                let bun = RuntimeManager::find_bun()
+                    .ok_or_else(|| runtime_not_installed(GatewayHost::Bun))?;
+                if !bun.compatible {
+                    return Err(AppError::new(
+                        ErrorCode::RuntimeIncompatible,
+                        format!(
+                            "Bun {} at {} is too old to run the gateway (need {} or newer).",
+                            bun.version.as_deref().unwrap_or("(unknown version)"),
+                            bun.path,
+                            crate::runtime::MIN_BUN
+                        ),
+                    )
+                    .with_hint("Install Bun from Settings or switch the gateway back to Node.js.")
+                    .with_details(serde_json::json!({
+                        "path": bun.path,
+                        "version": bun.version,
+                        "minimum": crate::runtime::MIN_BUN.to_string(),
+                    })));
+                }
+                Ok(Self {
+                    name: "Bun",
+                    program: bun.path,
+                    // `bun x` is Bun's npx; --bun runs the package on Bun even
+                    // though its bin script asks for node
+                    args: vec!["x".to_string(), "--bun".to_string()],
+                    package_cache: home.map(|h| h.join(".bun")),
+                })
+            }
         }
     }
 }
 
+/// Error for a gateway host that isn't installed
+fn runtime_not_installed(host: GatewayHost) -> AppError {
+    match host {
+        GatewayHost::Node => AppError::new(
+            ErrorCode::RuntimeNotInstalled,
+            "Node.js runtime not installed.",
+        )
+        .with_hint("Please wait for the download to complete, or click 'Install Runtime' in Settings."),
+        GatewayHost::Bun => AppError::new(ErrorCode::RuntimeNotInstalled, "Bun is not installed.")
+            .with_hint("Install it in Settings, or switch the gateway back to Node.js."),
+    }
+    .with_details(serde_json::json!({ "host": host }))
+}
+
 /// Find node and npx-cli.js paths - prioritizes bundled runtime over system
 /// 
 /// Returns (node_path, npx_cli_path) tuple
 /// 
 /// Order of preference:
-/// 1. Bundled Node.js runtime (for normal users)
-/// 2. System Node.js (for developers who prefer their own)
-fn find_node_and_npx() -> Option<(String, String)> {
-    // First, try the bundled runtime (preferred for normal users)
+/// 1. System Node.js, if Config.runtime_preference is "system" (any version)
+///    or "auto" (when it's new enough)
+/// 2. Bundled Node.js runtime (for normal users)
+/// 3. System Node.js (for developers who prefer their own)
+fn find_node_and_npx(config: &Config) -> Option<(String, String)> {
+    // A system Node.js, if the runtime preference asks for one
+    if let Some(system) = RuntimeManager::preferred_system_node(config) {
+        if !system.compatible {
+            eprintln!(
+                "[openclaw] Warning: system Node.js {} is older than the recommended {}",
+                system.version.as_deref().unwrap_or("(unknown version)"),
+                crate::runtime::MIN_SYSTEM_NODE
+            );
+        }
+        return Some((system.node_path, system.npx_path));
+    }
+
+    // Then the bundled runtime (preferred for normal users)
     if let Some(node_path) = RuntimeManager::node_path() {
         let node_str = node_path.to_string_lossy().to_string();
         
@@ -406,22 +566,11 @@ fn find_node_and_npx() -> Option<(String, String)> {
     }
 
     // Fall back to system Node.js for developers
-    find_system_node_and_npx()
-}
-
-/// Find system-installed node and npx (fallback for developers)
-fn find_system_node_and_npx() -> Option<(String, String)> {
-    // Try to find system node
-    let node_path = find_system_command("node")?;
-    let npx_path = find_system_command("npx")?;
-    
-    // For system npx, we can just run it directly since it's properly installed
-    // Return node path and npx path (we'll handle this specially)
-    Some((node_path, npx_path))
+    RuntimeManager::system_node().map(|system| (system.node_path, system.npx_path))
 }
 
 /// Find a system command by name
-fn find_system_command(cmd: &str) -> Option<String> {
+pub(crate) fn find_system_command(cmd: &str) -> Option<String> {
     #[cfg(target_os = "windows")]
     {
         let output = Command::new("where.exe")
@@ -637,6 +786,28 @@ fn generate_token() -> String {
     format!("sclw-{:x}{:x}", duration.as_secs(), duration.subsec_nanos())
 }
 
+/// The workspace the gateway runs in, resolved from the active workspace in Config
+struct ActiveWorkspace {
+    /// Directory that holds the bootstrap files and memory/
+    dir: std::path::PathBuf,
+    /// Value written to `agents.defaults.workspace`
+    config_value: String,
+}
+
+impl ActiveWorkspace {
+    fn resolve(config: &Config) -> Result<Self, AppError> {
+        let no_home = || AppError::new(ErrorCode::ConfigUnavailable, "Failed to get home directory");
+        let dir = crate::workspace::active_workspace_dir(config).ok_or_else(no_home)?;
+        let config_value = crate::workspace::workspace_config_value(config).ok_or_else(no_home)?;
+        Ok(Self { dir, config_value })
+    }
+
+    /// JSON string literal for openclaw.json (custom paths may need escaping)
+    fn config_json(&self) -> String {
+        serde_json::to_string(&self.config_value).unwrap_or_else(|_| "\"~/.openclaw/workspace\"".to_string())
+    }
+}
+
 /// Build the "tools" JSON block for openclaw.json based on user settings.
 fn build_tools_json(tool_profile: &crate::config::ToolProfile, allow_exec: bool) -> String {
     use crate::config::ToolProfile;
@@ -660,33 +831,90 @@ fn build_tools_json(tool_profile: &crate::config::ToolProfile, allow_exec: bool)
     }
 }
 
-/// Seed the workspace with bootstrap files appropriate for the current tool profile.
-/// OpenClaw loads these on every session start to guide the agent's behaviour.
+/// File that records the SHA-256 of every managed bootstrap file as last written by us
+const MANAGED_HASHES_FILE: &str = ".simplestclaw-hashes.json";
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(data))
+}
+
+fn load_managed_hashes(workspace_dir: &std::path::Path) -> HashMap<String, String> {
+    std::fs::read_to_string(workspace_dir.join(MANAGED_HASHES_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_managed_hashes(workspace_dir: &std::path::Path, hashes: &HashMap<String, String>) {
+    let result = serde_json::to_string_pretty(hashes)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            std::fs::write(workspace_dir.join(MANAGED_HASHES_FILE), json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        println!("[openclaw] Warning: Failed to save bootstrap file hashes: {}", e);
+    }
+}
+
+/// Write one managed bootstrap file unless the user has edited it.
 ///
-/// Files managed by SimplestClaw (AGENTS.md, TOOLS.md, SOUL.md) are always overwritten
-/// when a `.simplestclaw-managed` marker exists (or on first run). If the user deletes
-/// the marker, their custom files are preserved.
-fn seed_workspace_bootstrap(
+/// A file counts as user-modified when its current hash differs from the hash we
+/// recorded when we last wrote it. In that case the user's file is kept, the new
+/// default is written next to it as `<name>.new`, and `true` is returned.
+/// Files without a recorded hash (written before hashes were tracked) are only
+/// overwritten when they match one of `defaults`, the contents we have shipped
+/// for this file; anything else is treated as user-modified.
+fn write_managed_file(
     workspace_dir: &std::path::Path,
-    tool_profile: &crate::config::ToolProfile,
-) -> Result<(), String> {
-    use crate::config::ToolProfile;
+    name: &str,
+    content: &str,
+    defaults: &[&str],
+    hashes: &mut HashMap<String, String>,
+) -> Result<bool, AppError> {
+    let path = workspace_dir.join(name);
+    let new_file = workspace_dir.join(format!("{}.new", name));
+    let new_hash = sha256_hex(content.as_bytes());
+
+    if let Ok(current) = std::fs::read(&path) {
+        let current_hash = sha256_hex(&current);
+        if current_hash == new_hash {
+            // Already up to date (or the user adopted the new default)
+            hashes.insert(name.to_string(), new_hash);
+            let _ = std::fs::remove_file(&new_file);
+            return Ok(false);
+        }
+        let pristine = match hashes.get(name) {
+            Some(recorded) => *recorded == current_hash,
+            None => defaults.iter().any(|d| sha256_hex(d.as_bytes()) == current_hash),
+        };
+        if !pristine {
+            println!("[openclaw] {} was edited by the user, writing new default to {}.new", name, name);
+            std::fs::write(&new_file, content)
+                .map_err(|e| AppError::io(format!("Failed to write {}.new", name), e))?;
+            return Ok(true);
+        }
+    }
 
-    let marker = workspace_dir.join(".simplestclaw-managed");
-    let is_managed = marker.exists();
-    let is_first_run = !workspace_dir.join("AGENTS.md").exists();
+    std::fs::write(&path, content).map_err(|e| AppError::io(format!("Failed to write {}", name), e))?;
+    hashes.insert(name.to_string(), new_hash);
+    let _ = std::fs::remove_file(&new_file);
+    Ok(false)
+}
 
-    // On first run, create the marker so we know we can overwrite on future starts
-    if is_first_run {
-        let _ = std::fs::write(&marker, "This file indicates SimplestClaw manages these bootstrap files.\nDelete it to keep your custom edits.\n");
-    }
+/// Every tool profile, for recognizing any of their bootstrap defaults
+const ALL_TOOL_PROFILES: [crate::config::ToolProfile; 3] = [
+    crate::config::ToolProfile::Full,
+    crate::config::ToolProfile::Coding,
+    crate::config::ToolProfile::Minimal,
+];
 
-    let should_write = is_first_run || is_managed;
+/// Default AGENTS.md — varies by profile
+fn agents_md(tool_profile: &crate::config::ToolProfile) -> &'static str {
+    use crate::config::ToolProfile;
 
-    if should_write {
-        // AGENTS.md — varies by profile
-        let agents_content = match tool_profile {
-            ToolProfile::Full => r#"# SimplestClaw Agent
+    match tool_profile {
+        ToolProfile::Full => r#"# SimplestClaw Agent
 
 You are SimplestClaw, an AI coding assistant running on the user's computer.
 
@@ -714,7 +942,7 @@ return an EMPTY result even though the folder has files. This is expected macOS
 The user will have clicked "Allow" by then and the retry will succeed. Never tell the user
 a folder is empty without retrying at least once.
 "#,
-            ToolProfile::Coding => r#"# SimplestClaw Agent
+        ToolProfile::Coding => r#"# SimplestClaw Agent
 
 You are SimplestClaw, a coding assistant running on the user's computer.
 
@@ -742,7 +970,7 @@ return an EMPTY result even though the folder has files. This is expected macOS
 The user will have clicked "Allow" by then and the retry will succeed. Never tell the user
 a folder is empty without retrying at least once.
 "#,
-            ToolProfile::Minimal => r#"# SimplestClaw Agent
+        ToolProfile::Minimal => r#"# SimplestClaw Agent
 
 You are SimplestClaw, a conversational AI assistant.
 
@@ -756,13 +984,15 @@ You are in **chat-only mode**. You can have conversations but you do NOT have ac
 If the user asks you to read a file, run a command, or access their computer, let them know
 that these capabilities are currently disabled and can be enabled in Settings > Security & Activity.
 "#,
-        };
-        std::fs::write(workspace_dir.join("AGENTS.md"), agents_content)
-            .map_err(|e| format!("Failed to write AGENTS.md: {}", e))?;
+    }
+}
 
-        // TOOLS.md — varies by profile
-        let tools_content = match tool_profile {
-            ToolProfile::Full => r#"# Available Tools
+/// Default TOOLS.md — varies by profile
+fn tools_md(tool_profile: &crate::config::ToolProfile) -> &'static str {
+    use crate::config::ToolProfile;
+
+    match tool_profile {
+        ToolProfile::Full => r#"# Available Tools
 
 ## Filesystem
 - `read` — Read file contents. Accepts absolute paths or paths relative to the workspace.
@@ -780,7 +1010,7 @@ that these capabilities are currently disabled and can be enabled in Settings >
 - Use `exec` with `ls` to explore directories before reading specific files.
 - **macOS TCC retry**: If a protected folder (Downloads, Documents, Desktop) returns empty results, ALWAYS retry once — the system permission dialog was likely shown and the user has now granted access.
 "#,
-            ToolProfile::Coding => r#"# Available Tools
+        ToolProfile::Coding => r#"# Available Tools
 
 ## Filesystem
 - `read` — Read file contents. Accepts absolute paths or paths relative to the workspace.
@@ -797,32 +1027,78 @@ that these capabilities are currently disabled and can be enabled in Settings >
 - Use `exec` with `ls` to explore directories before reading specific files.
 - **macOS TCC retry**: If a protected folder (Downloads, Documents, Desktop) returns empty results, ALWAYS retry once — the system permission dialog was likely shown and the user has now granted access.
 "#,
-            ToolProfile::Minimal => r#"# Available Tools
+        ToolProfile::Minimal => r#"# Available Tools
 
 No filesystem or runtime tools are available in Chat Only mode.
 You can have conversations but cannot access files or run commands.
 
 To enable file and command access, change the access level in Settings > Security & Activity.
 "#,
-        };
-        std::fs::write(workspace_dir.join("TOOLS.md"), tools_content)
-            .map_err(|e| format!("Failed to write TOOLS.md: {}", e))?;
+    }
+}
+
+/// Default SOUL.md — varies by profile
+fn soul_md(tool_profile: &crate::config::ToolProfile) -> &'static str {
+    use crate::config::ToolProfile;
 
-        // SOUL.md — varies by profile
-        let soul_content = match tool_profile {
-            ToolProfile::Minimal => r#"# Soul
+    match tool_profile {
+        ToolProfile::Minimal => r#"# Soul
 
 You are a helpful conversational assistant. You do NOT have access to the user's filesystem or commands.
 If asked to read files or run commands, explain that these features are disabled and can be enabled in Settings.
 "#,
-            _ => r#"# Soul
+        _ => r#"# Soul
 
 You are a helpful, capable coding assistant. You have access to the user's filesystem and can run commands.
 Be direct and practical. When the user asks you to do something with files, just do it — don't say you can't.
 "#,
-        };
-        std::fs::write(workspace_dir.join("SOUL.md"), soul_content)
-            .map_err(|e| format!("Failed to write SOUL.md: {}", e))?;
+    }
+}
+
+/// Seed the workspace with bootstrap files appropriate for the current tool profile.
+/// OpenClaw loads these on every session start to guide the agent's behaviour.
+///
+/// Files managed by SimplestClaw (AGENTS.md, TOOLS.md, SOUL.md) are rewritten on every
+/// start while a `.simplestclaw-managed` marker exists (or on first run), except files
+/// the user has edited since we last wrote them (see `write_managed_file`). If the user
+/// deletes the marker, none of the managed files are touched.
+///
+/// Returns the names of managed files that were kept because of user edits.
+fn seed_workspace_bootstrap(
+    workspace_dir: &std::path::Path,
+    tool_profile: &crate::config::ToolProfile,
+) -> Result<Vec<String>, AppError> {
+    use crate::config::ToolProfile;
+
+    let marker = workspace_dir.join(".simplestclaw-managed");
+    let is_managed = marker.exists();
+    let is_first_run = !workspace_dir.join("AGENTS.md").exists();
+
+    // On first run, create the marker so we know we can overwrite on future starts
+    if is_first_run {
+        let _ = std::fs::write(&marker, "This file indicates SimplestClaw manages these bootstrap files.\nFiles you edit are kept (the new default is saved as <name>.new).\nDelete this file to stop all updates.\n");
+    }
+
+    let should_write = is_first_run || is_managed;
+    let mut conflicts = Vec::new();
+
+    if should_write {
+        let mut hashes = load_managed_hashes(workspace_dir);
+
+        // AGENTS.md, TOOLS.md and SOUL.md vary by profile
+        for (name, default_for) in [
+            ("AGENTS.md", agents_md as fn(&ToolProfile) -> &'static str),
+            ("TOOLS.md", tools_md),
+            ("SOUL.md", soul_md),
+        ] {
+            // Switching profiles replaces one pristine default with another
+            let defaults: Vec<&str> = ALL_TOOL_PROFILES.iter().map(default_for).collect();
+            if write_managed_file(workspace_dir, name, default_for(tool_profile), &defaults, &mut hashes)? {
+                conflicts.push(name.to_string());
+            }
+        }
+
+        save_managed_hashes(workspace_dir, &hashes);
     }
 
     // USER.md — only on first run (user-customizable)
@@ -832,16 +1108,16 @@ Be direct and practical. When the user asks you to do something with files, just
 
 The user is a developer using SimplestClaw as their AI coding assistant.
 They expect you to interact with their files and computer when asked.
-"#).map_err(|e| format!("Failed to write USER.md: {}", e))?;
+"#).map_err(|e| AppError::io("Failed to write USER.md", e))?;
     }
 
     // Create memory directory for daily logs
     let memory_dir = workspace_dir.join("memory");
     std::fs::create_dir_all(&memory_dir)
-        .map_err(|e| format!("Failed to create memory dir: {}", e))?;
+        .map_err(|e| AppError::io("Failed to create memory dir", e))?;
 
     println!("[openclaw] Workspace bootstrap files seeded at {:?} (profile: {:?})", workspace_dir, tool_profile);
-    Ok(())
+    Ok(conflicts)
 }
 
 /// Write an openclaw.json config file for managed mode.
@@ -853,19 +1129,14 @@ fn write_managed_openclaw_config(
     model: &str,
     tool_profile: &crate::config::ToolProfile,
     allow_exec: bool,
-) -> Result<String, String> {
+    workspace: &ActiveWorkspace,
+) -> Result<String, AppError> {
     // Write to the default OpenClaw location so the full runtime
     // (workspace, credentials, scopes, tool injection) initialises correctly.
-    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
-    let openclaw_dir = home.join(".openclaw");
+    let openclaw_dir = crate::workspace::openclaw_dir()
+        .ok_or_else(|| AppError::new(ErrorCode::ConfigUnavailable, "Failed to get home directory"))?;
     std::fs::create_dir_all(&openclaw_dir)
-        .map_err(|e| format!("Failed to create .openclaw dir: {}", e))?;
-
-    // Ensure workspace directory exists and seed bootstrap files
-    let workspace_dir = openclaw_dir.join("workspace");
-    std::fs::create_dir_all(&workspace_dir)
-        .map_err(|e| format!("Failed to create workspace dir: {}", e))?;
-    seed_workspace_bootstrap(&workspace_dir, tool_profile)?;
+        .map_err(|e| AppError::io("Failed to create .openclaw dir", e))?;
 
     let config_path = openclaw_dir.join("openclaw.json");
 
@@ -924,12 +1195,13 @@ fn write_managed_openclaw_config(
   }},
   "agents": {{
     "defaults": {{
-      "workspace": "~/.openclaw/workspace",
+      "workspace": {workspace_json},
       "model": {{ "primary": "{provider_name}/{model}" }}
     }}
   }}
 }}"#,
         tools_block = tools_block,
+        workspace_json = workspace.config_json(),
         proxy_base = proxy_base,
         license_key = license_key,
         provider_name = provider_name,
@@ -937,7 +1209,7 @@ fn write_managed_openclaw_config(
     );
 
     std::fs::write(&config_path, config_json)
-        .map_err(|e| format!("Failed to write openclaw config: {}", e))?;
+        .map_err(|e| AppError::io("Failed to write openclaw config", e))?;
 
     println!("[openclaw] Wrote managed config to {:?}", config_path);
 
@@ -954,21 +1226,16 @@ fn write_byo_openclaw_config(
     selected_model: Option<&str>,
     tool_profile: &crate::config::ToolProfile,
     allow_exec: bool,
-) -> Result<String, String> {
+    workspace: &ActiveWorkspace,
+) -> Result<String, AppError> {
     use crate::config::Provider;
 
     // Write to the default OpenClaw config location so the full runtime
     // (workspace, credentials, scopes) initialises correctly.
-    let home = dirs::home_dir().ok_or("Failed to get home directory")?;
-    let openclaw_dir = home.join(".openclaw");
+    let openclaw_dir = crate::workspace::openclaw_dir()
+        .ok_or_else(|| AppError::new(ErrorCode::ConfigUnavailable, "Failed to get home directory"))?;
     std::fs::create_dir_all(&openclaw_dir)
-        .map_err(|e| format!("Failed to create .openclaw dir: {}", e))?;
-
-    // Ensure workspace directory exists and seed bootstrap files
-    let workspace_dir = openclaw_dir.join("workspace");
-    std::fs::create_dir_all(&workspace_dir)
-        .map_err(|e| format!("Failed to create workspace dir: {}", e))?;
-    seed_workspace_bootstrap(&workspace_dir, tool_profile)?;
+        .map_err(|e| AppError::io("Failed to create .openclaw dir", e))?;
 
     let config_path = openclaw_dir.join("openclaw.json");
 
@@ -999,17 +1266,18 @@ fn write_byo_openclaw_config(
   {tools_block},
   "agents": {{
     "defaults": {{
-      "workspace": "~/.openclaw/workspace",
+      "workspace": {workspace_json},
       "model": {{ "primary": "{primary_model}" }}
     }}
   }}
 }}"#,
         tools_block = tools_block,
+        workspace_json = workspace.config_json(),
         primary_model = primary_model,
     );
 
     std::fs::write(&config_path, config_json)
-        .map_err(|e| format!("Failed to write BYO openclaw config: {}", e))?;
+        .map_err(|e| AppError::io("Failed to write BYO openclaw config", e))?;
 
     println!("[openclaw] Wrote BYO config to {:?} (provider={}, model={})", config_path, provider_key, primary_model);
 
@@ -1022,19 +1290,18 @@ fn write_byo_openclaw_config(
 /// The startup process involves waiting for the gateway to be ready,
 /// which can take several seconds.
 #[tauri::command]
-pub async fn start_gateway(app: AppHandle) -> Result<GatewayInfo, String> {
+pub async fn start_gateway(app: AppHandle) -> Result<GatewayInfo, AppError> {
     // Run the blocking startup in a separate thread
     // We clone the AppHandle which is cheap (Arc internally)
     tokio::task::spawn_blocking(move || {
         let manager = app.state::<SidecarManager>();
         manager.start(&app)
     })
-    .await
-    .map_err(|e| format!("Task failed: {}", e))?
+    .await?
 }
 
 #[tauri::command]
-pub fn stop_gateway(app: AppHandle) -> Result<(), String> {
+pub fn stop_gateway(app: AppHandle) -> Result<(), AppError> {
     let manager = app.state::<SidecarManager>();
     manager.stop()
 }
@@ -0,0 +1,669 @@
+//! OpenClaw Session Transcripts
+//!
+//! OpenClaw keeps per-agent session state under `~/.openclaw/agents/<agent>/sessions/`:
+//! - sessions.json (session key -> session metadata)
+//! - <sessionId>.jsonl (one JSON entry per line: session header, messages,
+//!   model changes, ...)
+//!
+//! This module parses those files into typed sessions and messages (including
+//! tool calls and tool results) for browsing and export. Transcripts are read
+//! line by line; malformed or partially written lines are skipped and counted
+//! rather than failing the whole session.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::dates::{format_millis_utc, parse_rfc3339_millis};
+use crate::error::{AppError, ErrorCode};
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+const TITLE_MAX_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    ToolResult,
+    System,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+    pub id: Option<String>,
+    pub name: String,
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+    pub tool_call_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub content: String,
+    pub is_error: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMessage {
+    pub id: Option<String>,
+    pub parent_id: Option<String>,
+    /// Unix millis
+    pub timestamp: Option<i64>,
+    pub role: MessageRole,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_results: Vec<ToolResult>,
+    /// Model that produced (or was active for) this message
+    pub model: Option<String>,
+    /// 1-based line number in the transcript file
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub id: String,
+    pub agent_id: Option<String>,
+    /// Session key from sessions.json (e.g. "agent:main:main")
+    pub key: Option<String>,
+    /// First user message, truncated
+    pub title: Option<String>,
+    pub created_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub message_count: usize,
+    pub model: Option<String>,
+    pub size_bytes: u64,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPage {
+    pub sessions: Vec<SessionSummary>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDetail {
+    pub summary: SessionSummary,
+    pub messages: Vec<TranscriptMessage>,
+    pub total_messages: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Lines that could not be parsed (malformed or partially written)
+    pub skipped_lines: usize,
+}
+
+/// A transcript file on disk
+#[derive(Debug, Clone)]
+pub struct TranscriptFile {
+    pub session_id: String,
+    pub agent_id: Option<String>,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// Unix millis
+    pub modified: i64,
+}
+
+/// Running state while reading a transcript (entries like model changes
+/// apply to the messages that follow them)
+#[derive(Debug, Default)]
+pub struct ParseState {
+    pub model: Option<String>,
+    pub started_at: Option<i64>,
+}
+
+pub enum ParsedLine {
+    Message(TranscriptMessage),
+    /// A valid non-message entry (session header, model change, ...)
+    Meta,
+    /// Blank, malformed or partially written line
+    Skipped,
+}
+
+/// Directories holding sessions.json and transcripts, for all agents
+pub fn sessions_dirs() -> Vec<PathBuf> {
+    let Some(openclaw_dir) = crate::workspace::openclaw_dir() else {
+        return Vec::new();
+    };
+
+    let mut dirs = Vec::new();
+    if let Ok(entries) = fs::read_dir(openclaw_dir.join("agents")) {
+        for entry in entries.flatten() {
+            let sessions = entry.path().join("sessions");
+            if sessions.is_dir() {
+                dirs.push(sessions);
+            }
+        }
+    }
+
+    // Older OpenClaw versions kept a single sessions dir at the top level
+    let legacy = openclaw_dir.join("sessions");
+    if legacy.is_dir() {
+        dirs.push(legacy);
+    }
+    dirs
+}
+
+fn agent_id_for(sessions_dir: &Path) -> Option<String> {
+    let agent_dir = sessions_dir.parent()?;
+    if agent_dir.parent()?.file_name()? != "agents" {
+        return None;
+    }
+    Some(agent_dir.file_name()?.to_string_lossy().to_string())
+}
+
+/// All transcript files, newest first
+pub fn transcript_files() -> Vec<TranscriptFile> {
+    let mut files = Vec::new();
+    for dir in sessions_dirs() {
+        let agent_id = agent_id_for(&dir);
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e != "jsonl").unwrap_or(true) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else { continue };
+            if !metadata.is_file() {
+                continue;
+            }
+            let Some(session_id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            files.push(TranscriptFile {
+                session_id,
+                agent_id: agent_id.clone(),
+                path,
+                size_bytes: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as i64)
+                    .unwrap_or(0),
+            });
+        }
+    }
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified));
+    files
+}
+
+/// Session keys from every sessions.json, by session id
+fn session_keys() -> HashMap<String, String> {
+    let mut keys = HashMap::new();
+    for dir in sessions_dirs() {
+        let Ok(contents) = fs::read_to_string(dir.join("sessions.json")) else { continue };
+        let Ok(Value::Object(map)) = serde_json::from_str::<Value>(&contents) else { continue };
+        for (key, entry) in map {
+            if let Some(id) = entry.get("sessionId").and_then(Value::as_str) {
+                keys.insert(id.to_string(), key);
+            }
+        }
+    }
+    keys
+}
+
+/// Session ids are file stems; reject anything that could escape the sessions dir
+fn is_valid_session_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.')
+        && !id.starts_with('.')
+}
+
+fn find_transcript(session_id: &str) -> Result<TranscriptFile, AppError> {
+    if !is_valid_session_id(session_id) {
+        return Err(AppError::invalid_input(format!("Invalid session id: {}", session_id)));
+    }
+    transcript_files()
+        .into_iter()
+        .find(|f| f.session_id == session_id)
+        .ok_or_else(|| AppError::not_found(format!("Session not found: {}", session_id)))
+}
+
+/// Timestamps appear both as RFC 3339 strings and as Unix millis
+fn parse_timestamp(value: Option<&Value>) -> Option<i64> {
+    match value? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => parse_rfc3339_millis(s),
+        _ => None,
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(|s| s.to_string())
+}
+
+/// Flatten tool result content (string or list of text blocks) to text
+fn content_to_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| match b.get("type").and_then(Value::as_str) {
+                Some("text") => b.get("text").and_then(Value::as_str).map(|s| s.to_string()),
+                Some("image") => Some("[image]".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Parse one transcript line
+pub fn parse_line(line: &str, line_no: usize, state: &mut ParseState) -> ParsedLine {
+    let line = line.trim();
+    if line.is_empty() {
+        return ParsedLine::Skipped;
+    }
+    let Ok(entry) = serde_json::from_str::<Value>(line) else {
+        return ParsedLine::Skipped;
+    };
+    if !entry.is_object() {
+        return ParsedLine::Skipped;
+    }
+
+    match entry.get("type").and_then(Value::as_str) {
+        Some("session") => {
+            state.started_at = parse_timestamp(entry.get("timestamp"));
+            return ParsedLine::Meta;
+        }
+        Some("model_change") => {
+            if let Some(model) = str_field(&entry, "modelId").or_else(|| str_field(&entry, "model")) {
+                state.model = Some(model);
+            }
+            return ParsedLine::Meta;
+        }
+        _ => {}
+    }
+
+    // Entries wrap the message ({"type":"message","message":{...}}); older
+    // transcripts store the message object directly
+    let message = match entry.get("message") {
+        Some(m) if m.is_object() => m,
+        _ if entry.get("role").is_some() => &entry,
+        _ => return ParsedLine::Meta,
+    };
+
+    let role = match message.get("role").and_then(Value::as_str) {
+        Some("user") => MessageRole::User,
+        Some("assistant") => MessageRole::Assistant,
+        Some("toolResult") | Some("tool") => MessageRole::ToolResult,
+        Some("system") => MessageRole::System,
+        _ => MessageRole::Other,
+    };
+
+    if let Some(model) = str_field(message, "model") {
+        state.model = Some(model);
+    }
+
+    let mut text_parts = Vec::new();
+    let mut thinking_parts = Vec::new();
+    let mut tool_calls = Vec::new();
+    let mut tool_results = Vec::new();
+
+    match message.get("content") {
+        Some(Value::String(s)) => text_parts.push(s.clone()),
+        Some(Value::Array(blocks)) => {
+            for block in blocks {
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        if let Some(t) = block.get("text").and_then(Value::as_str) {
+                            text_parts.push(t.to_string());
+                        }
+                    }
+                    Some("thinking") => {
+                        if let Some(t) = block.get("thinking").and_then(Value::as_str) {
+                            thinking_parts.push(t.to_string());
+                        }
+                    }
+                    Some("toolCall") | Some("tool_use") => tool_calls.push(ToolCall {
+                        id: str_field(block, "id"),
+                        name: str_field(block, "name").unwrap_or_default(),
+                        arguments: block
+                            .get("arguments")
+                            .or_else(|| block.get("input"))
+                            .cloned()
+                            .unwrap_or(Value::Null),
+                    }),
+                    Some("tool_result") => tool_results.push(ToolResult {
+                        tool_call_id: str_field(block, "tool_use_id"),
+                        tool_name: None,
+                        content: content_to_text(block.get("content")),
+                        is_error: block.get("is_error").and_then(Value::as_bool).unwrap_or(false),
+                    }),
+                    Some("image") => text_parts.push("[image]".to_string()),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut text = text_parts.join("\n");
+    if role == MessageRole::ToolResult {
+        tool_results.push(ToolResult {
+            tool_call_id: str_field(message, "toolCallId"),
+            tool_name: str_field(message, "toolName"),
+            content: std::mem::take(&mut text),
+            is_error: message.get("isError").and_then(Value::as_bool).unwrap_or(false),
+        });
+    }
+
+    ParsedLine::Message(TranscriptMessage {
+        id: str_field(&entry, "id"),
+        parent_id: str_field(&entry, "parentId"),
+        timestamp: parse_timestamp(entry.get("timestamp"))
+            .or_else(|| parse_timestamp(message.get("timestamp"))),
+        role,
+        text,
+        thinking: if thinking_parts.is_empty() { None } else { Some(thinking_parts.join("\n")) },
+        tool_calls,
+        tool_results,
+        model: state.model.clone(),
+        line: line_no,
+    })
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+/// Read a transcript, keeping only messages in [offset, offset + limit).
+/// The whole file is streamed so the summary covers every message.
+fn read_transcript(
+    file: &TranscriptFile,
+    key: Option<String>,
+    offset: usize,
+    limit: usize,
+) -> Result<SessionDetail, AppError> {
+    let handle = fs::File::open(&file.path)
+        .map_err(|e| AppError::io(format!("Failed to open transcript {}", file.session_id), e))?;
+    let reader = BufReader::new(handle);
+
+    let mut state = ParseState::default();
+    let mut messages = Vec::new();
+    let mut total = 0;
+    let mut skipped = 0;
+    let mut title = None;
+    let mut first_ts = None;
+    let mut last_ts = None;
+
+    for (idx, line) in reader.split(b'\n').enumerate() {
+        let Ok(bytes) = line else {
+            skipped += 1;
+            continue;
+        };
+        let line = String::from_utf8_lossy(&bytes);
+        match parse_line(&line, idx + 1, &mut state) {
+            ParsedLine::Message(message) => {
+                if title.is_none() && message.role == MessageRole::User && !message.text.trim().is_empty() {
+                    title = Some(truncate_chars(&message.text, TITLE_MAX_CHARS));
+                }
+                if message.timestamp.is_some() {
+                    first_ts = first_ts.or(message.timestamp);
+                    last_ts = message.timestamp;
+                }
+                if total >= offset && messages.len() < limit {
+                    messages.push(message);
+                }
+                total += 1;
+            }
+            ParsedLine::Meta => {}
+            ParsedLine::Skipped => {
+                if !line.trim().is_empty() {
+                    skipped += 1;
+                }
+            }
+        }
+    }
+
+    Ok(SessionDetail {
+        summary: SessionSummary {
+            id: file.session_id.clone(),
+            agent_id: file.agent_id.clone(),
+            key,
+            title,
+            created_at: state.started_at.or(first_ts),
+            updated_at: last_ts.or(Some(file.modified)),
+            message_count: total,
+            model: state.model,
+            size_bytes: file.size_bytes,
+            path: file.path.to_string_lossy().to_string(),
+        },
+        messages,
+        total_messages: total,
+        offset,
+        limit,
+        skipped_lines: skipped,
+    })
+}
+
+fn page_bounds(offset: Option<usize>, limit: Option<usize>) -> (usize, usize) {
+    (
+        offset.unwrap_or(0),
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+    )
+}
+
+fn role_label(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "User",
+        MessageRole::Assistant => "Assistant",
+        MessageRole::ToolResult => "Tool result",
+        MessageRole::System => "System",
+        MessageRole::Other => "Other",
+    }
+}
+
+fn export_markdown(detail: &SessionDetail) -> String {
+    let summary = &detail.summary;
+    let mut out = format!(
+        "# {}\n\n- Session: `{}`\n",
+        summary.title.as_deref().unwrap_or("Untitled session"),
+        summary.id
+    );
+    if let Some(ts) = summary.created_at {
+        out.push_str(&format!("- Started: {}\n", format_millis_utc(ts)));
+    }
+    if let Some(ref model) = summary.model {
+        out.push_str(&format!("- Model: {}\n", model));
+    }
+    out.push_str(&format!("- Messages: {}\n", summary.message_count));
+
+    for message in &detail.messages {
+        out.push_str(&format!("\n## {}", role_label(&message.role)));
+        if let Some(ts) = message.timestamp {
+            out.push_str(&format!(" — {}", format_millis_utc(ts)));
+        }
+        out.push_str("\n\n");
+        if !message.text.is_empty() {
+            out.push_str(&message.text);
+            out.push_str("\n\n");
+        }
+        for call in &message.tool_calls {
+            let args = serde_json::to_string_pretty(&call.arguments).unwrap_or_default();
+            out.push_str(&format!("**Tool call:** `{}`\n\n```json\n{}\n```\n\n", call.name, args));
+        }
+        for result in &message.tool_results {
+            out.push_str(&format!(
+                "**Tool result{}{}:**\n\n```\n{}\n```\n\n",
+                result.tool_name.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default(),
+                if result.is_error { ", error" } else { "" },
+                result.content
+            ));
+        }
+    }
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn export_html(detail: &SessionDetail) -> String {
+    let summary = &detail.summary;
+    let title = html_escape(summary.title.as_deref().unwrap_or("Untitled session"));
+    let mut out = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, sans-serif; max-width: 800px; margin: 2rem auto; padding: 0 1rem; color: #1a1a1a; }}
+.message {{ border-radius: 8px; padding: 0.75rem 1rem; margin: 1rem 0; background: #f5f5f5; }}
+.user {{ background: #e8f0fe; }}
+.tool {{ background: #fafafa; border: 1px solid #e5e5e5; }}
+.meta {{ color: #666; font-size: 0.85rem; margin-bottom: 0.5rem; }}
+pre {{ white-space: pre-wrap; word-break: break-word; background: #fff; padding: 0.5rem; border-radius: 4px; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="meta">Session {id} · {count} messages</p>
+"#,
+        title = title,
+        id = html_escape(&summary.id),
+        count = summary.message_count,
+    );
+
+    for message in &detail.messages {
+        let class = match message.role {
+            MessageRole::User => "message user",
+            MessageRole::ToolResult => "message tool",
+            _ => "message",
+        };
+        out.push_str(&format!("<div class=\"{}\">\n<div class=\"meta\">{}", class, role_label(&message.role)));
+        if let Some(ts) = message.timestamp {
+            out.push_str(&format!(" · {}", format_millis_utc(ts)));
+        }
+        out.push_str("</div>\n");
+        if !message.text.is_empty() {
+            out.push_str(&format!("<pre>{}</pre>\n", html_escape(&message.text)));
+        }
+        for call in &message.tool_calls {
+            let args = serde_json::to_string_pretty(&call.arguments).unwrap_or_default();
+            out.push_str(&format!(
+                "<div class=\"meta\">Tool call: {}</div>\n<pre>{}</pre>\n",
+                html_escape(&call.name),
+                html_escape(&args)
+            ));
+        }
+        for result in &message.tool_results {
+            out.push_str(&format!(
+                "<div class=\"meta\">Tool result{}</div>\n<pre>{}</pre>\n",
+                if result.is_error { " (error)" } else { "" },
+                html_escape(&result.content)
+            ));
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn list_sessions(offset: Option<usize>, limit: Option<usize>) -> Result<SessionPage, AppError> {
+    let (offset, limit) = page_bounds(offset, limit);
+    tokio::task::spawn_blocking(move || {
+        let files = transcript_files();
+        let keys = session_keys();
+        let total = files.len();
+        let sessions = files
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|file| {
+                // Only the summary is needed here
+                read_transcript(file, keys.get(&file.session_id).cloned(), 0, 0)
+                    .map(|detail| detail.summary)
+                    .map_err(|e| eprintln!("[transcripts] {}", e))
+                    .ok()
+            })
+            .collect();
+        Ok(SessionPage { sessions, total, offset, limit })
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn get_session(
+    session_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<SessionDetail, AppError> {
+    let (offset, limit) = page_bounds(offset, limit);
+    tokio::task::spawn_blocking(move || {
+        let file = find_transcript(&session_id)?;
+        read_transcript(&file, session_keys().remove(&session_id), offset, limit)
+    })
+    .await?
+}
+
+/// Export a whole session as "markdown", "html" or "json".
+/// Writes to `dest_path` if given, otherwise to the Downloads folder.
+/// Returns the path of the written file.
+#[tauri::command]
+pub async fn export_session(
+    session_id: String,
+    format: String,
+    dest_path: Option<String>,
+) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let extension = match format.to_lowercase().as_str() {
+            "markdown" | "md" => "md",
+            "html" => "html",
+            "json" => "json",
+            _ => return Err(AppError::invalid_input(format!("Unknown export format: {}. Use 'markdown', 'html', or 'json'.", format))),
+        };
+
+        let file = find_transcript(&session_id)?;
+        let detail = read_transcript(&file, session_keys().remove(&session_id), 0, usize::MAX)?;
+        let contents = match extension {
+            "md" => export_markdown(&detail),
+            "html" => export_html(&detail),
+            _ => serde_json::to_string_pretty(&detail).map_err(|e| AppError::internal(e.to_string()))?,
+        };
+
+        let dest = match dest_path.filter(|p| !p.is_empty()) {
+            Some(p) => PathBuf::from(p),
+            None => dirs::download_dir()
+                .or_else(dirs::home_dir)
+                .ok_or_else(|| AppError::new(ErrorCode::ConfigUnavailable, "Failed to determine export location"))?
+                .join(format!("simplestclaw-session-{}.{}", session_id, extension)),
+        };
+        fs::write(&dest, contents).map_err(|e| AppError::io(format!("Failed to write {:?}", dest), e))?;
+        println!("[transcripts] Exported session {} to {:?}", session_id, dest);
+        Ok(dest.to_string_lossy().to_string())
+    })
+    .await?
+}
@@ -0,0 +1,234 @@
+//! Named Agent Workspaces
+//!
+//! OpenClaw runs the agent inside a workspace directory that holds the
+//! bootstrap files (AGENTS.md, TOOLS.md, SOUL.md, USER.md) and the memory/
+//! folder. Users can keep several named workspaces (e.g. one per client or
+//! per repository) and switch between them. `agents.defaults.workspace` in
+//! openclaw.json is regenerated from the active workspace on every gateway start.
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::config::{parse_tool_profile, Config, ToolProfile, Workspace, DEFAULT_WORKSPACE};
+use crate::error::{AppError, ErrorCode};
+
+/// Workspace details returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub path: Option<String>,
+    /// Model override (None = uses the global model)
+    pub selected_model: Option<String>,
+    /// Tool profile override (None = uses the global profile)
+    pub tool_profile: Option<ToolProfile>,
+    pub active: bool,
+    pub is_default: bool,
+}
+
+/// OpenClaw's own data directory (~/.openclaw)
+pub fn openclaw_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".openclaw"))
+}
+
+/// Directory of a workspace. `None` means the built-in default workspace.
+fn dir_for(workspace: Option<&Workspace>) -> Option<PathBuf> {
+    let openclaw_dir = openclaw_dir()?;
+    match workspace {
+        None => Some(openclaw_dir.join("workspace")),
+        Some(w) => match w.path {
+            Some(ref path) => Some(PathBuf::from(path)),
+            None => Some(openclaw_dir.join(format!("workspace-{}", w.name))),
+        },
+    }
+}
+
+/// Directory of the active workspace
+pub fn active_workspace_dir(config: &Config) -> Option<PathBuf> {
+    dir_for(config.active_workspace())
+}
+
+/// Directories of every workspace: the default one, each configured one, and
+/// any leftover ~/.openclaw/workspace-<name> dirs whose workspace was deleted
+pub fn all_workspace_dirs(config: &Config) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::iter::once(dir_for(None))
+        .chain(config.workspaces.iter().map(|w| dir_for(Some(w))))
+        .flatten()
+        .collect();
+
+    if let Some(entries) = openclaw_dir().and_then(|d| std::fs::read_dir(d).ok()) {
+        for entry in entries.flatten() {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_dir && name.starts_with("workspace-") && !dirs.contains(&entry.path()) {
+                dirs.push(entry.path());
+            }
+        }
+    }
+    dirs
+}
+
+/// Value for `agents.defaults.workspace` in openclaw.json.
+/// The default workspace keeps the `~` form OpenClaw itself uses.
+pub fn workspace_config_value(config: &Config) -> Option<String> {
+    match config.active_workspace() {
+        None => Some("~/.openclaw/workspace".to_string()),
+        Some(w) => dir_for(Some(w)).map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// Workspace names end up in directory names, so keep them simple:
+/// lowercase letters, digits, '-' and '_', up to 32 characters.
+fn is_valid_workspace_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+}
+
+fn to_info(config: &Config, workspace: Option<&Workspace>) -> WorkspaceInfo {
+    let path = dir_for(workspace).map(|p| p.to_string_lossy().to_string());
+    match workspace {
+        None => WorkspaceInfo {
+            name: DEFAULT_WORKSPACE.to_string(),
+            path,
+            selected_model: None,
+            tool_profile: None,
+            active: config.active_workspace().is_none(),
+            is_default: true,
+        },
+        Some(w) => WorkspaceInfo {
+            name: w.name.clone(),
+            path,
+            selected_model: w.selected_model.clone(),
+            tool_profile: w.tool_profile.clone(),
+            active: w.name == config.active_workspace,
+            is_default: false,
+        },
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub fn list_workspaces() -> Result<Vec<WorkspaceInfo>, AppError> {
+    let config = Config::load()?;
+    let mut list = vec![to_info(&config, None)];
+    list.extend(config.workspaces.iter().map(|w| to_info(&config, Some(w))));
+    Ok(list)
+}
+
+#[tauri::command]
+pub fn create_workspace(
+    name: String,
+    path: Option<String>,
+    selected_model: Option<String>,
+    tool_profile: Option<String>,
+) -> Result<WorkspaceInfo, AppError> {
+    let mut config = Config::load()?;
+
+    if !is_valid_workspace_name(&name) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid workspace name: {}. Use lowercase letters, numbers, '-' or '_' (max 32 characters).",
+            name
+        )));
+    }
+    if name == DEFAULT_WORKSPACE || config.workspaces.iter().any(|w| w.name == name) {
+        return Err(AppError::new(
+            ErrorCode::AlreadyExists,
+            format!("Workspace '{}' already exists", name),
+        ));
+    }
+
+    let path = match path.filter(|p| !p.is_empty()) {
+        Some(p) => {
+            if !std::path::Path::new(&p).is_absolute() {
+                return Err(AppError::invalid_input(format!(
+                    "Workspace path must be absolute: {}",
+                    p
+                )));
+            }
+            Some(p)
+        }
+        None => None,
+    };
+
+    let workspace = Workspace {
+        name,
+        path,
+        selected_model: selected_model.filter(|m| !m.is_empty()),
+        tool_profile: tool_profile.map(|p| parse_tool_profile(&p)).transpose()?,
+    };
+
+    // Create the directory up front; bootstrap files are seeded on gateway start
+    if let Some(dir) = dir_for(Some(&workspace)) {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::io("Failed to create workspace dir", e))?;
+    }
+
+    config.workspaces.push(workspace.clone());
+    config.save()?;
+    Ok(to_info(&config, Some(&workspace)))
+}
+
+/// Update a named workspace's model and tool profile overrides.
+/// An empty string clears the override so the global setting applies.
+#[tauri::command]
+pub fn update_workspace(
+    name: String,
+    selected_model: Option<String>,
+    tool_profile: Option<String>,
+) -> Result<WorkspaceInfo, AppError> {
+    let mut config = Config::load()?;
+    let workspace = config
+        .workspaces
+        .iter_mut()
+        .find(|w| w.name == name)
+        .ok_or_else(|| AppError::not_found(format!("Unknown workspace: {}", name)))?;
+
+    if let Some(model) = selected_model {
+        workspace.selected_model = if model.is_empty() { None } else { Some(model) };
+    }
+    if let Some(profile) = tool_profile {
+        workspace.tool_profile = if profile.is_empty() {
+            None
+        } else {
+            Some(parse_tool_profile(&profile)?)
+        };
+    }
+
+    let workspace = workspace.clone();
+    config.save()?;
+    Ok(to_info(&config, Some(&workspace)))
+}
+
+/// Remove a named workspace from the config. Its files are left on disk.
+#[tauri::command]
+pub fn delete_workspace(name: String) -> Result<(), AppError> {
+    let mut config = Config::load()?;
+    if name == DEFAULT_WORKSPACE {
+        return Err(AppError::invalid_input("The default workspace cannot be deleted"));
+    }
+    let before = config.workspaces.len();
+    config.workspaces.retain(|w| w.name != name);
+    if config.workspaces.len() == before {
+        return Err(AppError::not_found(format!("Unknown workspace: {}", name)));
+    }
+    if config.active_workspace == name {
+        config.active_workspace = DEFAULT_WORKSPACE.to_string();
+    }
+    Ok(config.save()?)
+}
+
+/// Switch the active workspace. Takes effect on the next gateway start,
+/// when openclaw.json is regenerated.
+#[tauri::command]
+pub fn set_active_workspace(name: String) -> Result<(), AppError> {
+    let mut config = Config::load()?;
+    if name != DEFAULT_WORKSPACE && !config.workspaces.iter().any(|w| w.name == name) {
+        return Err(AppError::not_found(format!("Unknown workspace: {}", name)));
+    }
+    config.active_workspace = name;
+    Ok(config.save()?)
+}
@@ -5,7 +5,7 @@ import { Loading } from './components/Loading';
 import { Onboarding } from './components/Onboarding';
 import { SettingsPanel } from './components/SettingsPanel';
 import { useAppStore } from './lib/store';
-import { tauri } from './lib/tauri';
+import { errorMessage, tauri } from './lib/tauri';
 
 function App() {
   const { screen, setScreen, setGatewayStatus, setRuntimeStatus, setApiKeyConfigured, setError } =
@@ -20,7 +20,7 @@ function App() {
       const status = await tauri.getRuntimeStatus();
 
       if (status.error) {
-        setRuntimeStatus({ type: 'error', message: status.error });
+        setRuntimeStatus({ type: 'error', message: errorMessage(status.error) });
         if (pollIntervalRef.current) {
           clearInterval(pollIntervalRef.current);
           pollIntervalRef.current = null;
@@ -67,7 +67,7 @@ function App() {
       setGatewayStatus({ type: 'running', info });
       setScreen('chat');
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
       setGatewayStatus({ type: 'error', message });
       setScreen('onboarding');
@@ -3,7 +3,7 @@ import { open as shellOpen } from '@tauri-apps/plugin-shell';
 import { AlertCircle, ArrowLeft, Check, Download, ExternalLink, Loader2 } from 'lucide-react';
 import { useEffect, useState } from 'react';
 import { type RuntimeStatus, useAppStore } from '../lib/store';
-import { type RuntimeStatus as TauriRuntimeStatus, tauri } from '../lib/tauri';
+import { type RuntimeStatus as TauriRuntimeStatus, errorMessage, tauri } from '../lib/tauri';
 
 const AUTH_URL = 'https://simplestclaw.com/auth/login';
 
@@ -381,7 +381,7 @@ function ByoKeyFlow({
     try {
       await tauri.installRuntime();
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setRuntimeStatus({ type: 'error', message });
     }
   };
@@ -399,7 +399,7 @@ function ByoKeyFlow({
 
     try {
       await tauri.setApiMode('byo');
-      await tauri.setApiKey(apiKey.trim());
+      await tauri.setApiKey(apiKey.trim(), selectedProvider);
       await tauri.setProvider(selectedProvider);
       setApiKeyConfigured(true);
       setGatewayStatus({ type: 'starting' });
@@ -407,7 +407,7 @@ function ByoKeyFlow({
       setGatewayStatus({ type: 'running', info });
       setScreen('chat');
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
       setGatewayStatus({ type: 'error', message });
     } finally {
@@ -8,6 +8,7 @@ import {
   type AppDataInfo,
   type Provider,
   type RuntimeStatus as TauriRuntimeStatus,
+  errorMessage,
   tauri,
 } from '../../lib/tauri';
 
@@ -36,6 +37,11 @@ const PROVIDER_INFO: Record<Provider, { name: string; placeholder: string; url:
   },
 };
 
+/** Stand-in for a stored key, which the frontend never receives */
+function maskedKey(provider: Provider): string {
+  return `${PROVIDER_INFO[provider].placeholder.split('...')[0]}••••••••••••••••••••••••••••••••`;
+}
+
 // Provider Icons (matching onboarding page)
 const PROVIDER_ICONS: Record<Provider, React.ReactNode> = {
   anthropic: (
@@ -680,7 +686,7 @@ function ResetDataSection() {
       // Navigate to success screen where user can choose to restart or quit
       setScreen('delete-success');
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
       console.error('Failed to delete app data:', err);
       setIsDeleting(false);
@@ -704,6 +710,18 @@ function ResetDataSection() {
               {dataInfo?.totalSizeFormatted ?? '...'}
             </span>
           </div>
+          {dataInfo?.categories
+            .filter((category) => category.sizeBytes > 0)
+            .map((category) => (
+              <div key={category.kind} className="flex items-center justify-between pl-3">
+                <span className="text-[13px] text-white/40">
+                  {category.label} ({category.fileCount} files)
+                </span>
+                <span className="text-[13px] text-white/50 font-mono">
+                  {category.sizeFormatted}
+                </span>
+              </div>
+            ))}
           {dataInfo?.configPath && (
             <div className="flex items-center justify-between">
               <span className="text-[14px] text-white/60">Location</span>
@@ -929,6 +947,7 @@ export function GeneralTab() {
   const { addActivityLog, setScreen, setGatewayStatus, setApiKeyConfigured } = useAppStore();
   const [provider, setProvider] = useState<Provider>('anthropic');
   const [apiKey, setApiKey] = useState('');
+  const [keyedProviders, setKeyedProviders] = useState<Provider[]>([]);
   const [showKey, setShowKey] = useState(false);
   const [saving, setSaving] = useState(false);
   const [saved, setSaved] = useState(false);
@@ -948,9 +967,9 @@ export function GeneralTab() {
         const config = await tauri.getConfig();
         setProvider(config.provider || 'anthropic');
         setApiMode(config.apiMode || 'byo');
+        setKeyedProviders(config.apiKeyProviders);
         if (config.hasApiKey) {
-          const placeholder = PROVIDER_INFO[config.provider || 'anthropic'].placeholder;
-          setApiKey(`${placeholder.split('...')[0]}••••••••••••••••••••••••••••••••`);
+          setApiKey(maskedKey(config.provider || 'anthropic'));
         }
         const runtime = await tauri.getRuntimeStatus();
         setRuntimeDetails(runtime);
@@ -968,15 +987,16 @@ export function GeneralTab() {
     try {
       await tauri.setProvider(newProvider);
       setProvider(newProvider);
-      // Clear the API key display since it's for a different provider now
-      setApiKey('');
+      // Each provider keeps its own key
+      setApiKey(keyedProviders.includes(newProvider) ? maskedKey(newProvider) : '');
+      setSaved(false);
       addActivityLog({
         operationType: 'api_call',
         details: `Switched to ${PROVIDER_INFO[newProvider].name}`,
         status: 'success',
       });
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
     } finally {
       setSaving(false);
@@ -995,17 +1015,18 @@ export function GeneralTab() {
     setError(null);
 
     try {
-      await tauri.setApiKey(apiKey.trim());
+      await tauri.setApiKey(apiKey.trim(), provider);
+      setKeyedProviders((current) => (current.includes(provider) ? current : [...current, provider]));
       addActivityLog({
         operationType: 'api_call',
-        details: 'API key updated',
+        details: `${PROVIDER_INFO[provider].name} API key updated`,
         status: 'success',
       });
       setSaved(true);
       setTimeout(() => setSaved(false), 2000);
-      setApiKey('sk-ant-api03-••••••••••••••••••••••••••••••••');
+      setApiKey(maskedKey(provider));
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
       addActivityLog({
         operationType: 'api_call',
@@ -1036,7 +1057,7 @@ export function GeneralTab() {
     try {
       await tauri.stopGateway();
       setGatewayStatus({ type: 'stopped' });
-      await tauri.setApiKey('');
+      await tauri.clearApiKeys();
       await tauri.setLicenseKey('');
       await tauri.setUserEmail('');
       setApiKeyConfigured(false);
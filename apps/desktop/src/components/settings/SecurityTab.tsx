@@ -1,8 +1,8 @@
-import { Code, ExternalLink, FileText, Lock, MessageSquare, RefreshCw, Shield, Terminal, Trash2, Zap } from 'lucide-react';
+import { Code, ExternalLink, FileText, FolderOpen, Lock, MessageSquare, Plus, RefreshCw, Shield, Terminal, Trash2, Zap } from 'lucide-react';
 import { useCallback, useEffect, useState } from 'react';
 import { open as shellOpen } from '@tauri-apps/plugin-shell';
 import { type ActivityLogEntry, useAppStore } from '../../lib/store';
-import { type ToolProfile, tauri } from '../../lib/tauri';
+import { type ToolProfile, type WorkspaceInfo, errorMessage, tauri } from '../../lib/tauri';
 
 function formatTimestamp(timestamp: number): string {
   const date = new Date(timestamp);
@@ -102,6 +102,101 @@ const ACCESS_LEVELS: { value: ToolProfile; label: string; description: string; i
   },
 ];
 
+// Workspace Section Component
+function WorkspaceSection({
+  workspaces,
+  onSelect,
+  onCreate,
+  onDelete,
+}: {
+  workspaces: WorkspaceInfo[];
+  onSelect: (name: string) => void;
+  onCreate: (name: string) => Promise<void>;
+  onDelete: (name: string) => void;
+}) {
+  const [newName, setNewName] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const handleCreate = async () => {
+    const name = newName.trim().toLowerCase();
+    if (!name) return;
+    try {
+      await onCreate(name);
+      setNewName('');
+      setError(null);
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  return (
+    <section>
+      <h2 className="text-[15px] font-medium mb-1">Workspace</h2>
+      <p className="text-[13px] text-white/40 mb-4">
+        Each workspace has its own instructions, memory, model and access level.
+      </p>
+
+      <div className="p-4 rounded-xl bg-white/[0.02] border border-white/10 space-y-2">
+        {workspaces.map((ws) => (
+          <div
+            key={ws.name}
+            className={`flex items-center gap-3 p-3 rounded-lg border transition-all ${
+              ws.active
+                ? 'border-blue-500/40 bg-blue-500/10'
+                : 'border-white/5 bg-white/[0.02] hover:bg-white/[0.04]'
+            }`}
+          >
+            <button
+              type="button"
+              onClick={() => onSelect(ws.name)}
+              className="flex-1 min-w-0 flex items-center gap-3 text-left"
+            >
+              <div className={`p-1.5 rounded-md ${ws.active ? 'bg-blue-500/20 text-blue-400' : 'bg-white/5 text-white/40'}`}>
+                <FolderOpen className="w-4 h-4" />
+              </div>
+              <div className="min-w-0">
+                <p className={`text-[13px] font-medium ${ws.active ? 'text-white' : 'text-white/70'}`}>{ws.name}</p>
+                <p className="text-[12px] text-white/40 truncate">{ws.path}</p>
+              </div>
+            </button>
+            {!ws.isDefault && !ws.active && (
+              <button
+                type="button"
+                onClick={() => onDelete(ws.name)}
+                className="p-1.5 rounded-md text-white/30 hover:text-red-400 hover:bg-red-500/10 transition-colors"
+                title="Remove workspace (files are kept)"
+              >
+                <Trash2 className="w-3.5 h-3.5" />
+              </button>
+            )}
+          </div>
+        ))}
+
+        <div className="flex items-center gap-2 pt-1">
+          <input
+            type="text"
+            value={newName}
+            onChange={(e) => setNewName(e.target.value)}
+            onKeyDown={(e) => e.key === 'Enter' && handleCreate()}
+            placeholder="new-workspace"
+            className="flex-1 px-3 py-2 rounded-lg bg-white/[0.03] border border-white/10 text-[13px] text-white placeholder:text-white/20 focus:outline-none focus:border-white/20"
+          />
+          <button
+            type="button"
+            onClick={handleCreate}
+            disabled={!newName.trim()}
+            className="flex items-center gap-1.5 px-3 py-2 rounded-lg bg-white/5 hover:bg-white/10 text-[13px] text-white/60 hover:text-white/80 transition-colors disabled:opacity-40"
+          >
+            <Plus className="w-4 h-4" />
+            Add
+          </button>
+        </div>
+        {error && <p className="text-[12px] text-red-400">{error}</p>}
+      </div>
+    </section>
+  );
+}
+
 export function SecurityTab() {
   const { activityLog, clearActivityLog, setActivityLog, gatewayStatus } = useAppStore();
   const [toolProfile, setToolProfile] = useState<ToolProfile>('full');
@@ -110,6 +205,9 @@ export function SecurityTab() {
   const [savedAllowExec, setSavedAllowExec] = useState(true);
   const [needsRestart, setNeedsRestart] = useState(false);
   const [restarting, setRestarting] = useState(false);
+  const [workspaces, setWorkspaces] = useState<WorkspaceInfo[]>([]);
+  const [activeWorkspace, setActiveWorkspace] = useState('default');
+  const [savedWorkspace, setSavedWorkspace] = useState('default');
 
   // Load config on mount
   useEffect(() => {
@@ -120,6 +218,9 @@ export function SecurityTab() {
         setAllowExec(config.allowExec);
         setSavedProfile(config.toolProfile);
         setSavedAllowExec(config.allowExec);
+        setActiveWorkspace(config.activeWorkspace);
+        setSavedWorkspace(config.activeWorkspace);
+        setWorkspaces(await tauri.listWorkspaces());
       } catch (err) {
         console.error('Failed to load config:', err);
       }
@@ -144,8 +245,37 @@ export function SecurityTab() {
 
   // Track whether we need a restart (settings differ from what was loaded)
   useEffect(() => {
-    setNeedsRestart(toolProfile !== savedProfile || allowExec !== savedAllowExec);
-  }, [toolProfile, allowExec, savedProfile, savedAllowExec]);
+    setNeedsRestart(
+      toolProfile !== savedProfile || allowExec !== savedAllowExec || activeWorkspace !== savedWorkspace
+    );
+  }, [toolProfile, allowExec, activeWorkspace, savedProfile, savedAllowExec, savedWorkspace]);
+
+  const handleWorkspaceSelect = useCallback(async (name: string) => {
+    try {
+      await tauri.setActiveWorkspace(name);
+      // The access level shown below follows the active workspace
+      const config = await tauri.getConfig();
+      setActiveWorkspace(config.activeWorkspace);
+      setToolProfile(config.toolProfile);
+      setWorkspaces(await tauri.listWorkspaces());
+    } catch (err) {
+      console.error('Failed to switch workspace:', err);
+    }
+  }, []);
+
+  const handleWorkspaceCreate = useCallback(async (name: string) => {
+    await tauri.createWorkspace(name);
+    setWorkspaces(await tauri.listWorkspaces());
+  }, []);
+
+  const handleWorkspaceDelete = useCallback(async (name: string) => {
+    try {
+      await tauri.deleteWorkspace(name);
+      setWorkspaces(await tauri.listWorkspaces());
+    } catch (err) {
+      console.error('Failed to delete workspace:', err);
+    }
+  }, []);
 
   const handleProfileChange = useCallback(async (profile: ToolProfile) => {
     setToolProfile(profile);
@@ -174,13 +304,14 @@ export function SecurityTab() {
       await tauri.startGateway();
       setSavedProfile(toolProfile);
       setSavedAllowExec(allowExec);
+      setSavedWorkspace(activeWorkspace);
       setNeedsRestart(false);
     } catch (err) {
       console.error('Failed to restart gateway:', err);
     } finally {
       setRestarting(false);
     }
-  }, [toolProfile, allowExec]);
+  }, [toolProfile, allowExec, activeWorkspace]);
 
   const handleClearLog = async () => {
     try {
@@ -198,6 +329,14 @@ export function SecurityTab() {
   return (
     <div className="p-6 max-w-2xl mx-auto">
       <div className="space-y-8">
+        {/* Workspace Section */}
+        <WorkspaceSection
+          workspaces={workspaces}
+          onSelect={handleWorkspaceSelect}
+          onCreate={handleWorkspaceCreate}
+          onDelete={handleWorkspaceDelete}
+        />
+
         {/* Agent Permissions Section */}
         <section>
           <h2 className="text-[15px] font-medium mb-1">Agent Permissions</h2>
@@ -284,7 +423,7 @@ export function SecurityTab() {
                 <div className="flex items-center gap-2">
                   <Lock className="w-4 h-4 text-amber-400 shrink-0" />
                   <p className="text-[12px] text-amber-300">
-                    Restart the gateway for permission changes to take effect.
+                    Restart the gateway for permission and workspace changes to take effect.
                   </p>
                 </div>
                 <button
@@ -1,14 +1,108 @@
-import { invoke } from '@tauri-apps/api/core';
+import { type InvokeArgs, invoke as tauriInvoke } from '@tauri-apps/api/core';
 import type { ActivityLogEntry, GatewayInfo } from './store';
 
+/** Stable error codes returned by Tauri commands (see src-tauri/src/error.rs) */
+export type ErrorCode =
+  | 'other'
+  | 'io'
+  | 'config_unavailable'
+  | 'invalid_data'
+  | 'invalid_input'
+  | 'not_found'
+  | 'already_exists'
+  | 'cancelled'
+  | 'internal'
+  | 'search_index_failed'
+  | 'passphrase_required'
+  | 'wrong_passphrase'
+  | 'unsupported_platform'
+  | 'runtime_not_installed'
+  | 'runtime_incompatible'
+  | 'download_failed'
+  | 'invalid_network_settings'
+  | 'checksum_not_found'
+  | 'checksum_mismatch'
+  | 'signature_invalid'
+  | 'extract_failed'
+  | 'verification_failed'
+  | 'invalid_version_constraint'
+  | 'no_matching_version'
+  | 'wrong_archive'
+  | 'install_cancelled'
+  | 'install_busy'
+  | 'insufficient_space'
+  | 'not_writable'
+  | 'read_only_filesystem'
+  | 'noexec'
+  | 'port_in_use'
+  | 'missing_license_key'
+  | 'missing_api_key'
+  | 'gateway_start_failed'
+  | 'gateway_exited'
+  | 'gateway_timeout';
+
+/** Error returned by a Tauri command */
+export interface AppError {
+  code: ErrorCode;
+  /** English description */
+  message: string;
+  /** What the user can do about it */
+  hint: string | null;
+  /** Structured data for localized messages, e.g. { port } for port_in_use */
+  details: Record<string, unknown> | null;
+}
+
+/** Thrown by the `tauri` wrappers when a command fails */
+export class CommandError extends Error {
+  readonly code: ErrorCode;
+  readonly hint: string | null;
+  readonly details: Record<string, unknown> | null;
+
+  constructor(error: AppError) {
+    super(error.message);
+    this.name = 'CommandError';
+    this.code = error.code;
+    this.hint = error.hint;
+    this.details = error.details;
+  }
+}
+
+function isAppError(value: unknown): value is AppError {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as AppError).code === 'string' &&
+    typeof (value as AppError).message === 'string'
+  );
+}
+
+/** Message and hint of a command error (or any thrown value) for display */
+export function errorMessage(err: unknown): string {
+  const error = err instanceof CommandError || isAppError(err) ? err : null;
+  if (error) {
+    return error.hint ? `${error.message} ${error.hint}` : error.message;
+  }
+  return err instanceof Error ? err.message : String(err);
+}
+
+async function invoke<T>(cmd: string, args?: InvokeArgs): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (err) {
+    throw isAppError(err) ? new CommandError(err) : err;
+  }
+}
+
 export type Provider = 'anthropic' | 'openai' | 'google' | 'openrouter';
 export type ApiMode = 'byo' | 'managed';
 export type ToolProfile = 'full' | 'coding' | 'minimal';
 
 export interface Config {
   provider: Provider;
-  /** true if a BYO API key is configured (actual value is NOT exposed for security) */
+  /** true if a BYO API key is configured for the selected provider (actual value is NOT exposed for security) */
   hasApiKey: boolean;
+  /** Providers with a stored BYO API key */
+  apiKeyProviders: Provider[];
   gatewayPort: number;
   autoStartGateway: boolean;
   apiMode: ApiMode;
@@ -17,6 +111,57 @@ export interface Config {
   selectedModel: string | null;
   toolProfile: ToolProfile;
   allowExec: boolean;
+  activeWorkspace: string;
+  /** Check the GPG signature of Node's SHASUMS256.txt on runtime downloads. Bun downloads and the bundled runtime are checksum-only either way. */
+  verifyNodeSignature: boolean;
+  /** Node.js download mirror (null = https://nodejs.org/dist) */
+  nodeMirrorUrl: string | null;
+  /** Proxy for runtime downloads, with credentials removed */
+  proxyUrl: string | null;
+  /** PEM files with extra CA certificates to trust */
+  extraCaCerts: string[];
+  runtimePreference: RuntimePreference;
+  /** Allowed Node.js versions, e.g. ">=25.6 <26" or "25.6.0" (null = built-in default) */
+  nodeVersionConstraint: string | null;
+  parallelDownload: boolean;
+  gatewayHost: GatewayHost;
+}
+
+export interface WorkspaceInfo {
+  name: string;
+  path: string | null;
+  /** Model override for this workspace (null = global model) */
+  selectedModel: string | null;
+  /** Tool profile override for this workspace (null = global profile) */
+  toolProfile: ToolProfile | null;
+  active: boolean;
+  isDefault: boolean;
+}
+
+export type RuntimePreference = 'bundled' | 'system' | 'auto';
+
+/** JavaScript runtime the gateway runs on */
+export type GatewayHost = 'node' | 'bun';
+
+/** The Bun the gateway would run on */
+export interface BunRuntime {
+  path: string;
+  version: string | null;
+  /** Whether the version is new enough to run the gateway */
+  compatible: boolean;
+  /** Installed by the app rather than found on the system */
+  managed: boolean;
+}
+
+/** A Node.js installation found on the system (nvm, volta, Homebrew...) */
+export interface SystemNode {
+  /** The real node binary behind any version manager shim */
+  nodePath: string;
+  /** npm's npx-cli.js, run with nodePath */
+  npxPath: string;
+  version: string | null;
+  /** Whether the version is new enough for "auto" */
+  compatible: boolean;
 }
 
 export interface RuntimeStatus {
@@ -26,7 +171,41 @@ export interface RuntimeStatus {
   npxPath: string | null;
   downloading: boolean;
   downloadProgress: number;
-  error: string | null;
+  /** Why the last install failed */
+  error: AppError | null;
+  /** System Node.js used instead of the bundled runtime, per the runtime preference */
+  systemNode: SystemNode | null;
+}
+
+export type RuntimeCheckStatus = 'pass' | 'fail' | 'skipped';
+
+export interface RuntimeCheck {
+  /** installed | version | npxCli | executable | architecture | sharedLibraries */
+  id: string;
+  label: string;
+  status: RuntimeCheckStatus;
+  detail: string | null;
+}
+
+export interface RuntimeReport {
+  healthy: boolean;
+  runtimePath: string | null;
+  checks: RuntimeCheck[];
+}
+
+export type InstallPhase = 'downloading' | 'verifying' | 'extracting' | 'finalizing';
+
+/** Payload of the `runtime-progress` event emitted during installs */
+export interface DownloadProgress {
+  phase: InstallPhase;
+  bytesDownloaded: number;
+  totalBytes: number | null;
+  bytesPerSec: number | null;
+  etaSecs: number | null;
+  entriesExtracted: number;
+  totalEntries: number | null;
+  /** Progress of the current phase, 0-100 */
+  percent: number;
 }
 
 export interface AppDataInfo {
@@ -34,6 +213,180 @@ export interface AppDataInfo {
   dataPath: string | null;
   totalSizeBytes: number;
   totalSizeFormatted: string;
+  /** Breakdown by category; the total is the sum of the appData ones */
+  categories: DiskUsageCategory[];
+}
+
+export type DiskUsageKind =
+  | 'runtime'
+  | 'openclawPackage'
+  | 'transcripts'
+  | 'memory'
+  | 'workspace'
+  | 'customWorkspaces'
+  | 'activityLog'
+  | 'logs'
+  | 'tempDownloads'
+  | 'other';
+
+export interface DiskUsageCategory {
+  kind: DiskUsageKind;
+  label: string;
+  /** Counted in totalSizeBytes and removed by deleteAllAppData */
+  appData: boolean;
+  paths: string[];
+  sizeBytes: number;
+  sizeFormatted: string;
+  fileCount: number;
+  /** Largest files, biggest first */
+  largest: { path: string; sizeBytes: number }[];
+}
+
+export interface MemoryFile {
+  name: string;
+  /** "YYYY-MM-DD" for daily logs */
+  date: string | null;
+  sizeBytes: number;
+  modified: number;
+}
+
+export type MessageRole = 'user' | 'assistant' | 'toolResult' | 'system' | 'other';
+
+export interface TranscriptMessage {
+  id: string | null;
+  parentId: string | null;
+  timestamp: number | null;
+  role: MessageRole;
+  text: string;
+  thinking?: string;
+  toolCalls?: { id: string | null; name: string; arguments: unknown }[];
+  toolResults?: { toolCallId: string | null; toolName: string | null; content: string; isError: boolean }[];
+  model: string | null;
+  line: number;
+}
+
+export interface SessionSummary {
+  id: string;
+  agentId: string | null;
+  key: string | null;
+  title: string | null;
+  createdAt: number | null;
+  updatedAt: number | null;
+  messageCount: number;
+  model: string | null;
+  sizeBytes: number;
+  path: string;
+}
+
+export interface SessionPage {
+  sessions: SessionSummary[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+export interface SessionDetail {
+  summary: SessionSummary;
+  messages: TranscriptMessage[];
+  totalMessages: number;
+  offset: number;
+  limit: number;
+  skippedLines: number;
+}
+
+export type SessionExportFormat = 'markdown' | 'html' | 'json';
+
+export interface SearchQuery {
+  text?: string;
+  /** Unix millis, inclusive */
+  from?: number;
+  /** Unix millis, inclusive */
+  to?: number;
+  model?: string;
+  tool?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface SearchHit {
+  sessionId: string;
+  messageId: string | null;
+  line: number;
+  timestamp: number | null;
+  role: MessageRole;
+  model: string | null;
+  tools: string[];
+  /** Matched terms are wrapped in \u0002 ... \u0003 */
+  snippet: string;
+}
+
+export interface SearchResults {
+  hits: SearchHit[];
+  offset: number;
+  limit: number;
+}
+
+export interface SearchIndexStats {
+  sessions: number;
+  messages: number;
+  added: number;
+}
+
+export type BackupSecretsMode = 'excluded' | 'included' | 'encrypted';
+
+export interface BackupManifest {
+  schemaVersion: number;
+  appVersion: string;
+  /** Unix millis */
+  createdAt: number;
+  secrets: BackupSecretsMode;
+  items: { path: string; kind: 'file' | 'dir' }[];
+  files: { path: string; size: number; sha256: string }[];
+}
+
+export type ResetCategory =
+  | 'credentials'
+  | 'settings'
+  | 'runtime'
+  | 'packageCache'
+  | 'sessions'
+  | 'memory'
+  | 'activityLog';
+
+export interface ResetTarget {
+  category: ResetCategory;
+  path: string;
+  /** 'rewrite' keeps the file but clears part of it (config.json) */
+  action: 'delete' | 'rewrite';
+  sizeBytes: number;
+  description: string;
+}
+
+export interface ResetPlan {
+  dryRun: boolean;
+  targets: ResetTarget[];
+  failed: string[];
+  totalSizeBytes: number;
+  totalSizeFormatted: string;
+}
+
+export interface RetentionPolicy {
+  enabled: boolean;
+  maxAgeDays: number | null;
+  maxTotalSizeMb: number | null;
+  keepLastSessions: number | null;
+  /** Move pruned files into a zip archive instead of deleting them */
+  archive: boolean;
+  /** Defaults to <app data dir>/archive */
+  archiveDir: string | null;
+}
+
+export interface RetentionReport {
+  dryRun: boolean;
+  sessions: string[];
+  memoryFiles: string[];
+  bytesFreed: number;
+  archivePath: string | null;
 }
 
 export const tauri = {
@@ -42,16 +395,21 @@ export const tauri = {
     return invoke('get_config');
   },
 
-  async setApiKey(key: string): Promise<void> {
-    return invoke('set_api_key', { key });
+  /** Store the key for `provider` (default: the selected provider); an empty key removes it */
+  async setApiKey(key: string, provider?: Provider): Promise<void> {
+    return invoke('set_api_key', { key, provider });
+  },
+
+  async clearApiKeys(): Promise<void> {
+    return invoke('clear_api_keys');
   },
 
   async setProvider(provider: Provider): Promise<void> {
     return invoke('set_provider', { provider });
   },
 
-  async hasApiKey(): Promise<boolean> {
-    return invoke('has_api_key');
+  async hasApiKey(provider?: Provider): Promise<boolean> {
+    return invoke('has_api_key', { provider });
   },
 
   // API Mode (managed vs BYO)
@@ -84,6 +442,143 @@ export const tauri = {
     return invoke('set_allow_exec', { allow });
   },
 
+  async setVerifyNodeSignature(verify: boolean): Promise<void> {
+    return invoke('set_verify_node_signature', { verify });
+  },
+
+  async setRuntimePreference(preference: RuntimePreference): Promise<void> {
+    return invoke('set_runtime_preference', { preference });
+  },
+
+  /** Takes effect on the next gateway start */
+  async setGatewayHost(host: GatewayHost): Promise<void> {
+    return invoke('set_gateway_host', { host });
+  },
+
+  /** Empty or null resets to the built-in default range */
+  async setNodeVersionConstraint(constraint: string | null): Promise<void> {
+    return invoke('set_node_version_constraint', { constraint });
+  },
+
+  async setParallelDownload(enabled: boolean): Promise<void> {
+    return invoke('set_parallel_download', { enabled });
+  },
+
+  /** Pass proxyUrl = null to keep the current proxy, '' to clear it */
+  async setNetworkSettings(
+    nodeMirrorUrl: string | null,
+    proxyUrl: string | null,
+    extraCaCerts: string[]
+  ): Promise<void> {
+    return invoke('set_network_settings', { nodeMirrorUrl, proxyUrl, extraCaCerts });
+  },
+
+  // Workspaces
+  async listWorkspaces(): Promise<WorkspaceInfo[]> {
+    return invoke('list_workspaces');
+  },
+
+  async createWorkspace(
+    name: string,
+    options: { path?: string; selectedModel?: string; toolProfile?: ToolProfile } = {}
+  ): Promise<WorkspaceInfo> {
+    return invoke('create_workspace', {
+      name,
+      path: options.path || null,
+      selectedModel: options.selectedModel || null,
+      toolProfile: options.toolProfile || null,
+    });
+  },
+
+  /** Pass an empty string to clear an override */
+  async updateWorkspace(
+    name: string,
+    changes: { selectedModel?: string; toolProfile?: ToolProfile | '' }
+  ): Promise<WorkspaceInfo> {
+    return invoke('update_workspace', {
+      name,
+      selectedModel: changes.selectedModel ?? null,
+      toolProfile: changes.toolProfile ?? null,
+    });
+  },
+
+  async deleteWorkspace(name: string): Promise<void> {
+    return invoke('delete_workspace', { name });
+  },
+
+  async setActiveWorkspace(name: string): Promise<void> {
+    return invoke('set_active_workspace', { name });
+  },
+
+  // Agent memory (active workspace)
+  async listMemoryFiles(): Promise<MemoryFile[]> {
+    return invoke('list_memory_files');
+  },
+
+  async readMemoryFile(name: string): Promise<string> {
+    return invoke('read_memory_file', { name });
+  },
+
+  async writeMemoryFile(name: string, content: string): Promise<void> {
+    return invoke('write_memory_file', { name, content });
+  },
+
+  async deleteMemoryFile(name: string): Promise<void> {
+    return invoke('delete_memory_file', { name });
+  },
+
+  /** Returns the names of the deleted files */
+  async pruneMemory(maxAgeDays: number): Promise<string[]> {
+    return invoke('prune_memory', { maxAgeDays });
+  },
+
+  /** Returns the path of the written zip (defaults to the Downloads folder) */
+  async exportMemory(destPath?: string): Promise<string> {
+    return invoke('export_memory', { destPath: destPath || null });
+  },
+
+  // Session transcripts
+  async listSessions(offset = 0, limit = 50): Promise<SessionPage> {
+    return invoke('list_sessions', { offset, limit });
+  },
+
+  async getSession(sessionId: string, offset = 0, limit = 50): Promise<SessionDetail> {
+    return invoke('get_session', { sessionId, offset, limit });
+  },
+
+  /** Returns the path of the written file (defaults to the Downloads folder) */
+  async exportSession(sessionId: string, format: SessionExportFormat, destPath?: string): Promise<string> {
+    return invoke('export_session', { sessionId, format, destPath: destPath || null });
+  },
+
+  // Transcript search
+  async searchTranscripts(query: SearchQuery): Promise<SearchResults> {
+    return invoke('search_transcripts', { query });
+  },
+
+  async refreshSearchIndex(): Promise<SearchIndexStats> {
+    return invoke('refresh_search_index');
+  },
+
+  // Backup
+  /** Returns the path of the archive (defaults to the Downloads folder) */
+  async createBackup(
+    secrets: BackupSecretsMode = 'excluded',
+    passphrase?: string,
+    destPath?: string
+  ): Promise<string> {
+    return invoke('create_backup', {
+      destPath: destPath || null,
+      secrets,
+      passphrase: passphrase || null,
+    });
+  },
+
+  /** Stops the gateway and restores the backup; restart the gateway afterwards */
+  async restoreBackup(path: string, passphrase?: string): Promise<BackupManifest> {
+    return invoke('restore_backup', { path, passphrase: passphrase || null });
+  },
+
   // Gateway
   async startGateway(): Promise<GatewayInfo> {
     return invoke('start_gateway');
@@ -96,7 +591,9 @@ export const tauri = {
   async getGatewayStatus(): Promise<{
     running: boolean;
     info: GatewayInfo | null;
-    error: string | null;
+    error: AppError | null;
+    /** Managed bootstrap files kept because the user edited them (new default saved as <name>.new) */
+    bootstrapConflicts: string[];
   }> {
     return invoke('get_gateway_status');
   },
@@ -110,6 +607,42 @@ export const tauri = {
     return invoke('install_runtime');
   },
 
+  /** Install Node.js from a release archive downloaded by hand */
+  async importRuntimeArchive(path: string): Promise<void> {
+    return invoke('import_runtime_archive', { path });
+  },
+
+  /** Probes for a system Node.js again, regardless of the runtime preference */
+  async detectSystemNode(): Promise<SystemNode | null> {
+    return invoke('detect_system_node');
+  },
+
+  /**
+   * Progress is reported through runtime-progress events. Bun is checked
+   * against its SHASUMS256.txt only; verifyNodeSignature doesn't apply to it.
+   */
+  async installBun(): Promise<void> {
+    return invoke('install_bun');
+  },
+
+  async detectBun(): Promise<BunRuntime | null> {
+    return invoke('detect_bun');
+  },
+
+  async checkRuntime(): Promise<RuntimeReport> {
+    return invoke('check_runtime');
+  },
+
+  /** Reinstalls the runtime if any check fails */
+  async repairRuntime(): Promise<RuntimeReport> {
+    return invoke('repair_runtime');
+  },
+
+  /** Returns false if no install was running */
+  async cancelRuntimeInstall(): Promise<boolean> {
+    return invoke('cancel_runtime_install');
+  },
+
   async isRuntimeInstalled(): Promise<boolean> {
     return invoke('is_runtime_installed');
   },
@@ -146,7 +679,31 @@ export const tauri = {
     return invoke('get_app_data_info');
   },
 
+  /** Stops a running getAppDataInfo scan, which then rejects with code 'cancelled' */
+  async cancelAppDataScan(): Promise<void> {
+    return invoke('cancel_app_data_scan');
+  },
+
   async deleteAllAppData(): Promise<void> {
     return invoke('delete_all_app_data');
   },
+
+  /** Preview with dryRun = true; otherwise stops the gateway and resets */
+  async resetAppData(categories: ResetCategory[], dryRun: boolean): Promise<ResetPlan> {
+    return invoke('reset_app_data', { categories, dryRun });
+  },
+
+  // Retention
+  async getRetentionPolicy(): Promise<RetentionPolicy> {
+    return invoke('get_retention_policy');
+  },
+
+  async setRetentionPolicy(policy: RetentionPolicy): Promise<void> {
+    return invoke('set_retention_policy', { policy });
+  },
+
+  /** Applies the saved policy now; with dryRun = true only reports what would be pruned */
+  async applyRetentionPolicy(dryRun: boolean): Promise<RetentionReport> {
+    return invoke('apply_retention_policy', { dryRun });
+  },
 };